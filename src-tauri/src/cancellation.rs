@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::error::NeuralError;
+
+// ===== In-flight Request Registry =====
+
+/// Tracks cancellation tokens for in-flight requests, keyed by the frontend-visible request id
+#[derive(Default)]
+pub struct RequestRegistry {
+    tokens: Mutex<HashMap<String, (u64, CancellationToken)>>,
+    next_generation: AtomicU64,
+}
+
+impl RequestRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `future` until it completes or `cancel(request_id)` is called.
+    ///
+    /// Cancelling drops the future, which aborts the underlying HTTP request and releases
+    /// any lock the future was holding or waiting for.
+    pub async fn run<F, T>(&self, request_id: &str, future: F) -> Result<T, NeuralError>
+    where
+        F: Future<Output = Result<T, NeuralError>>,
+    {
+        let (generation, token) = self.register(request_id);
+
+        let result = tokio::select! {
+            _ = token.cancelled() => {
+                tracing::info!("🛑 Request {} cancelled", request_id);
+                Err(NeuralError::Cancelled { request_id: request_id.to_string() })
+            }
+            result = future => result,
+        };
+
+        self.finish(request_id, generation);
+        result
+    }
+
+    /// Cancel the request with the given id; returns false if it is not running
+    pub fn cancel(&self, request_id: &str) -> bool {
+        match self.tokens.lock() {
+            Ok(mut tokens) => match tokens.remove(request_id) {
+                Some((_, token)) => {
+                    token.cancel();
+                    true
+                }
+                None => false,
+            },
+            Err(e) => {
+                tracing::error!("Failed to acquire request registry lock: {}", e);
+                false
+            }
+        }
+    }
+
+    fn register(&self, request_id: &str) -> (u64, CancellationToken) {
+        let generation = self.next_generation.fetch_add(1, Ordering::Relaxed);
+        let token = CancellationToken::new();
+
+        if let Ok(mut tokens) = self.tokens.lock() {
+            // Reusing an id supersedes the earlier request
+            if let Some((_, previous)) = tokens.insert(request_id.to_string(), (generation, token.clone())) {
+                previous.cancel();
+            }
+        }
+
+        (generation, token)
+    }
+
+    fn finish(&self, request_id: &str, generation: u64) {
+        if let Ok(mut tokens) = self.tokens.lock() {
+            // Only remove our own entry, not one registered later under the same id
+            if tokens.get(request_id).map(|(g, _)| *g) == Some(generation) {
+                tokens.remove(request_id);
+            }
+        }
+    }
+}
+
+/// Use the caller-supplied request id or generate a fresh one
+pub fn resolve_request_id(request_id: Option<String>) -> String {
+    request_id
+        .filter(|id| !id.trim().is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancel_aborts_running_future() {
+        let registry = Arc::new(RequestRegistry::new());
+
+        let runner = {
+            let registry = Arc::clone(&registry);
+            tokio::spawn(async move {
+                registry.run("req-1", async {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    Ok::<_, NeuralError>("finished")
+                }).await
+            })
+        };
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(registry.cancel("req-1"), "Running request should be cancellable");
+
+        let result = tokio::time::timeout(Duration::from_secs(1), runner).await
+            .expect("Cancelled request should return immediately")
+            .unwrap();
+        assert_eq!(result, Err(NeuralError::Cancelled { request_id: "req-1".to_string() }));
+    }
+
+    #[tokio::test]
+    async fn test_completed_request_is_unregistered() {
+        let registry = RequestRegistry::new();
+
+        let result = registry.run("req-2", async { Ok::<_, NeuralError>(42) }).await;
+        assert_eq!(result, Ok(42));
+        assert!(!registry.cancel("req-2"), "Finished request should no longer be registered");
+    }
+
+    #[test]
+    fn test_resolve_request_id() {
+        assert_eq!(resolve_request_id(Some("abc".to_string())), "abc");
+        assert!(!resolve_request_id(None).is_empty());
+        assert!(!resolve_request_id(Some("  ".to_string())).trim().is_empty());
+    }
+}
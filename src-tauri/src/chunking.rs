@@ -0,0 +1,300 @@
+// ===== Paragraph Splitting =====
+
+/// A slice of the source text: either text to translate or the whitespace around it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+    Text(&'a str),
+    Spacing(&'a str), // Copied verbatim so the translation keeps the original layout
+}
+
+/// Split text on blank lines. Concatenating the segments reproduces the input exactly,
+/// and text segments never start or end with whitespace.
+pub fn split_paragraphs(text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut cursor = 0; // End of the last segment pushed
+    let mut paragraph: Option<(usize, usize)> = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+
+        if content.trim().is_empty() {
+            if let Some((start, end)) = paragraph.take() {
+                push_paragraph(text, &mut segments, &mut cursor, start, end);
+            }
+        } else {
+            let start = paragraph.map_or(offset + content.len() - content.trim_start().len(), |(start, _)| start);
+            paragraph = Some((start, offset + content.trim_end().len()));
+        }
+
+        offset += line.len();
+    }
+
+    if let Some((start, end)) = paragraph {
+        push_paragraph(text, &mut segments, &mut cursor, start, end);
+    }
+    if cursor < text.len() {
+        segments.push(Segment::Spacing(&text[cursor..]));
+    }
+
+    segments
+}
+
+fn push_paragraph<'a>(text: &'a str, segments: &mut Vec<Segment<'a>>, cursor: &mut usize, start: usize, end: usize) {
+    if start > *cursor {
+        segments.push(Segment::Spacing(&text[*cursor..start]));
+    }
+    segments.push(Segment::Text(&text[start..end]));
+    *cursor = end;
+}
+
+// ===== Token Budgeting =====
+
+/// Cost of one character in quarter tokens: CJK characters are roughly a token each,
+/// other scripts average about four characters per token
+fn char_weight(c: char) -> usize {
+    let cjk = ('\u{3040}'..='\u{30FF}').contains(&c) // Hiragana, Katakana
+        || ('\u{3400}'..='\u{9FFF}').contains(&c)     // CJK Unified Ideographs
+        || ('\u{AC00}'..='\u{D7AF}').contains(&c)     // Hangul
+        || ('\u{FF00}'..='\u{FFEF}').contains(&c);    // Full-width forms
+    if cjk { 4 } else { 1 }
+}
+
+fn token_weight(text: &str) -> usize {
+    text.chars().map(char_weight).sum()
+}
+
+/// Rough token count, good enough to keep chunks inside a model's context
+pub fn estimate_tokens(text: &str) -> usize {
+    token_weight(text).div_ceil(4)
+}
+
+/// Like `split_paragraphs`, but paragraphs over `max_tokens` are split at sentence
+/// boundaries, and single overlong sentences between words (or characters, for CJK)
+pub fn split_chunks(text: &str, max_tokens: usize) -> Vec<Segment<'_>> {
+    let max_weight = max_tokens.max(1) * 4;
+    let mut segments = Vec::new();
+
+    for segment in split_paragraphs(text) {
+        match segment {
+            Segment::Text(paragraph) if token_weight(paragraph) > max_weight => {
+                for piece in pack(paragraph, max_weight, Granularity::Sentences) {
+                    push_piece(&mut segments, piece);
+                }
+            }
+            Segment::Spacing(spacing) => push_spacing(&mut segments, spacing),
+            text => segments.push(text),
+        }
+    }
+
+    segments
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Granularity {
+    Sentences,
+    Words,
+    Chars,
+}
+
+/// Greedily group consecutive units of `text` into pieces within `max_weight`.
+/// The pieces are contiguous and together cover `text` exactly.
+fn pack(text: &str, max_weight: usize, granularity: Granularity) -> Vec<&str> {
+    let units: Vec<&str> = match granularity {
+        Granularity::Sentences => split_sentences(text),
+        Granularity::Words => text.split_inclusive(char::is_whitespace).collect(),
+        Granularity::Chars => text.char_indices().map(|(i, c)| &text[i..i + c.len_utf8()]).collect(),
+    };
+
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut end = 0;
+    let mut weight = 0;
+
+    for unit in units {
+        let unit_weight = token_weight(unit);
+
+        if end > start && weight + unit_weight > max_weight {
+            pieces.push(&text[start..end]);
+            start = end;
+            weight = 0;
+        }
+
+        if unit_weight > max_weight && granularity != Granularity::Chars {
+            let finer = match granularity {
+                Granularity::Sentences => Granularity::Words,
+                _ => Granularity::Chars,
+            };
+            pieces.extend(pack(unit, max_weight, finer));
+            start = end + unit.len();
+            end = start;
+            continue;
+        }
+
+        end += unit.len();
+        weight += unit_weight;
+    }
+
+    if end > start {
+        pieces.push(&text[start..end]);
+    }
+
+    pieces
+}
+
+/// Sentences including their trailing whitespace. Line breaks also end a sentence,
+/// since text extracted from PDFs often wraps mid-paragraph.
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((_, c)) = chars.next() {
+        let ends_sentence = match c {
+            '。' | '！' | '？' | '\n' => true,
+            '.' | '!' | '?' => chars.peek().is_none_or(|&(_, next)| next.is_whitespace() || is_closing(next)),
+            _ => false,
+        };
+        if !ends_sentence {
+            continue;
+        }
+
+        while chars.peek().is_some_and(|&(_, next)| is_closing(next)) {
+            chars.next();
+        }
+        while chars.peek().is_some_and(|&(_, next)| next.is_whitespace()) {
+            chars.next();
+        }
+
+        let end = chars.peek().map_or(text.len(), |&(i, _)| i);
+        sentences.push(&text[start..end]);
+        start = end;
+    }
+
+    if start < text.len() {
+        sentences.push(&text[start..]);
+    }
+
+    sentences
+}
+
+fn is_closing(c: char) -> bool {
+    matches!(c, '"' | '\'' | ')' | ']' | '」' | '』' | '）' | '”' | '’')
+}
+
+/// Push a packed piece as text, moving its surrounding whitespace into spacing segments
+fn push_piece<'a>(segments: &mut Vec<Segment<'a>>, piece: &'a str) {
+    let trimmed_start = piece.trim_start();
+    let text = trimmed_start.trim_end();
+
+    push_spacing(segments, &piece[..piece.len() - trimmed_start.len()]);
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    push_spacing(segments, &trimmed_start[text.len()..]);
+}
+
+fn push_spacing<'a>(segments: &mut Vec<Segment<'a>>, spacing: &'a str) {
+    if !spacing.is_empty() {
+        segments.push(Segment::Spacing(spacing));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reassemble(segments: &[Segment<'_>]) -> String {
+        segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) | Segment::Spacing(text) => *text,
+            })
+            .collect()
+    }
+
+    fn texts<'a>(segments: &[Segment<'a>]) -> Vec<&'a str> {
+        segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Text(text) => Some(*text),
+                Segment::Spacing(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_split_paragraphs_keeps_original_spacing() {
+        let text = "\n  First line\nstill first.\n\n\nSecond paragraph.  \r\n \r\nThird\n";
+        let segments = split_paragraphs(text);
+
+        assert_eq!(segments, vec![
+            Segment::Spacing("\n  "),
+            Segment::Text("First line\nstill first."),
+            Segment::Spacing("\n\n\n"),
+            Segment::Text("Second paragraph."),
+            Segment::Spacing("  \r\n \r\n"),
+            Segment::Text("Third"),
+            Segment::Spacing("\n"),
+        ]);
+        assert_eq!(reassemble(&segments), text);
+    }
+
+    #[test]
+    fn test_split_paragraphs_single_and_empty() {
+        assert_eq!(split_paragraphs("こんにちは"), vec![Segment::Text("こんにちは")]);
+        assert_eq!(split_paragraphs(" \n\n"), vec![Segment::Spacing(" \n\n")]);
+        assert!(split_paragraphs("").is_empty());
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        assert_eq!(estimate_tokens("日本語"), 3);
+        assert_eq!(estimate_tokens(""), 0);
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        assert_eq!(
+            split_sentences("Smith arrived. \"Really?\" she asked!  Version 2.5 shipped\nwrapped line"),
+            vec!["Smith arrived. ", "\"Really?\" ", "she asked!  ", "Version 2.5 shipped\n", "wrapped line"]
+        );
+        assert_eq!(split_sentences("今日は晴れ。明日は「雨」？ああ"), vec!["今日は晴れ。", "明日は「雨」？", "ああ"]);
+    }
+
+    #[test]
+    fn test_split_chunks_respects_budget() {
+        let paragraph = "One two three four. Five six seven eight. Nine ten eleven twelve.";
+        let text = format!("Short.\n\n{}\n", paragraph);
+        let segments = split_chunks(&text, 6);
+
+        assert_eq!(reassemble(&segments), text);
+        assert_eq!(texts(&segments), vec![
+            "Short.",
+            "One two three four.",
+            "Five six seven eight.",
+            "Nine ten eleven twelve.",
+        ]);
+        assert!(texts(&segments).iter().all(|chunk| estimate_tokens(chunk) <= 6));
+    }
+
+    #[test]
+    fn test_split_chunks_breaks_overlong_sentences() {
+        let words = "alpha beta gamma delta epsilon zeta eta theta";
+        let segments = split_chunks(words, 4);
+        assert_eq!(reassemble(&segments), words);
+        assert!(texts(&segments).len() > 1);
+        assert!(texts(&segments).iter().all(|chunk| estimate_tokens(chunk) <= 4));
+
+        let kanji = "漢字漢字漢字漢字漢字";
+        let segments = split_chunks(kanji, 4);
+        assert_eq!(texts(&segments), vec!["漢字漢字", "漢字漢字", "漢字"]);
+    }
+
+    #[test]
+    fn test_split_chunks_leaves_small_paragraphs_alone() {
+        let text = "First.\n\nSecond. Still second.";
+        assert_eq!(split_chunks(text, 100), split_paragraphs(text));
+    }
+}
@@ -0,0 +1,735 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sysinfo::System;
+
+use crate::engine::EngineKind;
+use crate::error::NeuralError;
+use crate::language::Language;
+use crate::ollama::{model_name_matches, RECOMMENDED_MODELS};
+
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+pub const DEFAULT_OPENAI_URL: &str = "http://localhost:8080/v1"; // llama-server's default; LM Studio uses :1234/v1
+pub const DEFAULT_LIBRETRANSLATE_URL: &str = "http://localhost:5000";
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 5;
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 2;
+const SETTINGS_FILE_NAME: &str = "settings.json";
+const GIB: u64 = 1024 * 1024 * 1024;
+
+// ===== Backend Configuration =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct BackendConfig {
+    pub base_url: String,
+    pub auth_header: Option<String>, // Sent verbatim as the Authorization header, e.g. "Bearer <token>"
+    pub timeout_secs: u64, // Limit for each attempt of a request, body included; for streams, the longest wait between chunks
+    pub connect_timeout_secs: u64, // Limit for establishing the connection, so a dead server fails fast
+    pub max_concurrent_requests: usize, // Generation requests allowed in flight at once
+    pub retry: RetryPolicy,
+}
+
+impl Default for BackendConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_OLLAMA_URL.to_string(),
+            auth_header: None,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+impl BackendConfig {
+    /// Validate user input and return a cleaned-up copy (trimmed URL, empty auth header dropped)
+    pub fn normalized(&self) -> Result<Self, NeuralError> {
+        let base_url = normalize_base_url(&self.base_url, "Ollama")?;
+
+        if self.timeout_secs == 0 {
+            return Err(NeuralError::InvalidInput("Request timeout must be at least 1 second".to_string()));
+        }
+
+        if self.connect_timeout_secs == 0 {
+            return Err(NeuralError::InvalidInput("Connect timeout must be at least 1 second".to_string()));
+        }
+
+        if self.max_concurrent_requests == 0 {
+            return Err(NeuralError::InvalidInput("Concurrency limit must be at least 1".to_string()));
+        }
+
+        let auth_header = self.auth_header
+            .as_ref()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+
+        Ok(Self {
+            base_url,
+            auth_header,
+            timeout_secs: self.timeout_secs,
+            connect_timeout_secs: self.connect_timeout_secs,
+            max_concurrent_requests: self.max_concurrent_requests,
+            retry: self.retry.normalized()?,
+        })
+    }
+}
+
+/// How often a request is retried on the same model before moving on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub max_retries: u32, // Attempts after the first one; 0 disables retries
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub retryable_statuses: Vec<u16>, // HTTP statuses treated as transient
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 8_000,
+            retryable_statuses: vec![408, 429, 500, 502, 503, 504],
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn normalized(&self) -> Result<Self, NeuralError> {
+        if self.max_retries > 10 {
+            return Err(NeuralError::InvalidInput("At most 10 retries are allowed".to_string()));
+        }
+
+        if self.initial_backoff_ms > self.max_backoff_ms {
+            return Err(NeuralError::InvalidInput("Initial backoff cannot exceed the maximum backoff".to_string()));
+        }
+
+        if let Some(status) = self.retryable_statuses.iter().find(|status| !(400..=599).contains(*status)) {
+            return Err(NeuralError::InvalidInput(format!("Status {} is not an HTTP error status", status)));
+        }
+
+        let mut retryable_statuses = self.retryable_statuses.clone();
+        retryable_statuses.sort_unstable();
+        retryable_statuses.dedup();
+
+        Ok(Self { retryable_statuses, ..self.clone() })
+    }
+
+    /// Delay before retry number `retry` (0-based): the initial backoff doubled each time, capped at the maximum
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u64.checked_shl(retry).unwrap_or(u64::MAX);
+        Duration::from_millis(self.initial_backoff_ms.saturating_mul(factor).min(self.max_backoff_ms))
+    }
+
+    pub fn is_retryable_status(&self, status: u16) -> bool {
+        self.retryable_statuses.contains(&status)
+    }
+}
+
+// ===== OpenAI-compatible Server Configuration =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OpenAiConfig {
+    pub base_url: String, // Including the version prefix, e.g. "http://localhost:1234/v1"
+    pub api_key: Option<String>, // Sent as a bearer token; local servers usually need none
+    pub model: String, // Model id sent with each request; empty lets the server use whatever it has loaded
+    pub timeout_secs: u64,
+}
+
+impl Default for OpenAiConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_OPENAI_URL.to_string(),
+            api_key: None,
+            model: String::new(),
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl OpenAiConfig {
+    pub fn normalized(&self) -> Result<Self, NeuralError> {
+        let base_url = normalize_base_url(&self.base_url, "OpenAI-compatible server")?;
+
+        if self.timeout_secs == 0 {
+            return Err(NeuralError::InvalidInput("Request timeout must be at least 1 second".to_string()));
+        }
+
+        let api_key = self.api_key
+            .as_ref()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+
+        Ok(Self {
+            base_url,
+            api_key,
+            model: self.model.trim().to_string(),
+            timeout_secs: self.timeout_secs,
+        })
+    }
+}
+
+// ===== LibreTranslate Server Configuration =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct LibreTranslateConfig {
+    pub base_url: String,
+    pub api_key: Option<String>, // Only needed when the server runs with --api-keys
+    pub timeout_secs: u64,
+}
+
+impl Default for LibreTranslateConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_LIBRETRANSLATE_URL.to_string(),
+            api_key: None,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl LibreTranslateConfig {
+    pub fn normalized(&self) -> Result<Self, NeuralError> {
+        let base_url = normalize_base_url(&self.base_url, "LibreTranslate")?;
+
+        if self.timeout_secs == 0 {
+            return Err(NeuralError::InvalidInput("Request timeout must be at least 1 second".to_string()));
+        }
+
+        let api_key = self.api_key
+            .as_ref()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+
+        Ok(Self {
+            base_url,
+            api_key,
+            timeout_secs: self.timeout_secs,
+        })
+    }
+}
+
+fn normalize_base_url(url: &str, server: &str) -> Result<String, NeuralError> {
+    let base_url = url.trim().trim_end_matches('/').to_string();
+
+    if !(base_url.starts_with("http://") || base_url.starts_with("https://")) {
+        return Err(NeuralError::InvalidInput(format!("Invalid {} URL '{}': must start with http:// or https://", server, url)));
+    }
+
+    Ok(base_url)
+}
+
+// ===== Model Preferences =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ModelPreferences {
+    pub preferred_model: Option<String>,
+    pub fallback_models: Vec<String>, // Tried in order when the preferred model is unavailable
+}
+
+impl Default for ModelPreferences {
+    fn default() -> Self {
+        Self {
+            preferred_model: None,
+            fallback_models: RECOMMENDED_MODELS.iter().map(|model| model.to_string()).collect(),
+        }
+    }
+}
+
+impl ModelPreferences {
+    /// Trim names and drop blanks and duplicates so the fallback order is exactly what the user sees
+    pub fn normalized(&self) -> Self {
+        let preferred_model = self.preferred_model
+            .as_ref()
+            .map(|model| model.trim().to_string())
+            .filter(|model| !model.is_empty());
+
+        let mut fallback_models: Vec<String> = Vec::new();
+        for model in &self.fallback_models {
+            let model = model.trim();
+            if !model.is_empty() && !fallback_models.iter().any(|m| m == model) {
+                fallback_models.push(model.to_string());
+            }
+        }
+
+        Self {
+            preferred_model,
+            fallback_models,
+        }
+    }
+
+    /// Models to try for a request: an explicit choice is used alone, otherwise preferred then fallbacks
+    pub fn candidates(&self, explicit_model: Option<&str>) -> Vec<String> {
+        if let Some(model) = explicit_model.map(str::trim).filter(|model| !model.is_empty()) {
+            return vec![model.to_string()];
+        }
+
+        let mut candidates: Vec<String> = Vec::new();
+        for model in self.preferred_model.iter().chain(self.fallback_models.iter()) {
+            if !candidates.contains(model) {
+                candidates.push(model.clone());
+            }
+        }
+        candidates
+    }
+}
+
+// ===== Automatic Direction =====
+
+/// Languages `auto` translations choose their target from: the first one that isn't the
+/// detected source. The default turns Japanese into English and everything else into Japanese.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct DirectionPreferences {
+    pub languages: Vec<Language>, // Most preferred first
+}
+
+impl Default for DirectionPreferences {
+    fn default() -> Self {
+        Self {
+            languages: vec![Language::Japanese, Language::English],
+        }
+    }
+}
+
+impl DirectionPreferences {
+    /// Drop duplicates; at least two languages must remain
+    pub fn normalized(&self) -> Result<Self, NeuralError> {
+        let mut languages: Vec<Language> = Vec::new();
+        for &language in &self.languages {
+            if !languages.contains(&language) {
+                languages.push(language);
+            }
+        }
+
+        if languages.len() < 2 {
+            return Err(NeuralError::InvalidInput("Choose at least two languages to translate between".to_string()));
+        }
+
+        Ok(Self { languages })
+    }
+
+    /// Target for text in `source`: the most preferred language other than the source itself
+    pub fn target_for(&self, source: Language) -> Option<Language> {
+        self.languages.iter().copied().find(|&language| language != source)
+    }
+}
+
+// ===== Inference Tuning =====
+
+/// Ollama runtime and sampling options. `None` leaves the choice to Ollama.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct InferenceProfile {
+    pub num_thread: Option<usize>,
+    pub num_gpu: Option<i32>, // Layers offloaded to the GPU; 0 forces CPU-only
+    pub use_mmap: bool,
+    pub use_mlock: bool,
+    pub num_ctx: usize,
+    pub temperature: f32,
+    pub top_p: f32,
+    pub num_predict: usize,
+}
+
+impl Default for InferenceProfile {
+    fn default() -> Self {
+        Self::detect()
+    }
+}
+
+impl InferenceProfile {
+    /// Recommended options for this machine, from its physical core count and installed memory
+    pub fn detect() -> Self {
+        let mut system = System::new();
+        system.refresh_memory();
+        Self::for_machine(system.physical_core_count(), system.total_memory())
+    }
+
+    pub fn for_machine(physical_cores: Option<usize>, total_memory_bytes: u64) -> Self {
+        let memory_gib = total_memory_bytes / GIB;
+
+        Self {
+            num_thread: physical_cores, // Hyperthreads slow generation down rather than up
+            num_gpu: None,              // Ollama detects Metal/CUDA/ROCm itself and falls back to CPU
+            use_mmap: true,
+            use_mlock: memory_gib >= 16, // Pinning a model on small machines starves everything else
+            // Room for the system prompt, the previous chunk and its translation, and the output
+            num_ctx: if memory_gib >= 32 { 8192 } else { 4096 },
+            temperature: 0.3, // Lower for more consistent translations
+            top_p: 0.9,
+            num_predict: 1024,
+        }
+    }
+
+    pub fn normalized(&self) -> Result<Self, NeuralError> {
+        if self.num_thread == Some(0) {
+            return Err(NeuralError::InvalidInput("Thread count must be at least 1".to_string()));
+        }
+
+        if self.num_ctx < 512 {
+            return Err(NeuralError::InvalidInput("Context size must be at least 512 tokens".to_string()));
+        }
+
+        if self.num_predict == 0 {
+            return Err(NeuralError::InvalidInput("Output limit must be at least 1 token".to_string()));
+        }
+
+        if !(0.0..=2.0).contains(&self.temperature) {
+            return Err(NeuralError::InvalidInput("Temperature must be between 0 and 2".to_string()));
+        }
+
+        if !(self.top_p > 0.0 && self.top_p <= 1.0) {
+            return Err(NeuralError::InvalidInput("top_p must be greater than 0 and at most 1".to_string()));
+        }
+
+        Ok(Self {
+            num_gpu: self.num_gpu.filter(|layers| *layers >= 0),
+            ..self.clone()
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct InferenceSettings {
+    pub default_profile: InferenceProfile,
+    pub model_profiles: BTreeMap<String, InferenceProfile>, // Per-model overrides, keyed by model name
+}
+
+impl InferenceSettings {
+    /// The override saved for `model`, tolerating an implicit `:latest` tag on either side
+    pub fn profile_for(&self, model: &str) -> &InferenceProfile {
+        self.model_profiles
+            .get(model)
+            .or_else(|| {
+                self.model_profiles
+                    .iter()
+                    .find(|(name, _)| model_name_matches(model, name) || model_name_matches(name, model))
+                    .map(|(_, profile)| profile)
+            })
+            .unwrap_or(&self.default_profile)
+    }
+}
+
+// ===== Persisted Settings File =====
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub default_engine: EngineKind, // Used when a command doesn't name an engine
+    pub backend: BackendConfig,
+    pub openai: OpenAiConfig,
+    pub libretranslate: LibreTranslateConfig,
+    pub models: ModelPreferences,
+    pub inference: InferenceSettings,
+    pub direction: DirectionPreferences,
+}
+
+impl AppSettings {
+    /// Load settings from the default location, falling back to defaults if missing or unreadable
+    pub fn load() -> Self {
+        let path = get_default_settings_path();
+
+        if !path.exists() {
+            return Self::default();
+        }
+
+        match Self::load_from(&path) {
+            Ok(settings) => settings,
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable settings file {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, NeuralError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| NeuralError::Io(format!("Failed to read settings file: {}", e)))?;
+
+        serde_json::from_str(&content)
+            .map_err(|e| NeuralError::Parse(format!("Failed to parse settings file: {}", e)))
+    }
+
+    pub fn save(&self) -> Result<(), NeuralError> {
+        self.save_to(&get_default_settings_path())
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), NeuralError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| NeuralError::Io(format!("Failed to create settings directory: {}", e)))?;
+        }
+
+        let json_content = serde_json::to_string_pretty(self)
+            .map_err(|e| NeuralError::Parse(format!("Failed to serialize settings: {}", e)))?;
+
+        fs::write(path, json_content)
+            .map_err(|e| NeuralError::Io(format!("Failed to write settings file: {}", e)))
+    }
+}
+
+fn get_default_config_directory() -> PathBuf {
+    if cfg!(target_os = "macos") {
+        PathBuf::from(format!("{}/Library/Application Support/NeuraL/", std::env::var("HOME").unwrap_or_default()))
+    } else if cfg!(target_os = "windows") {
+        PathBuf::from(format!("{}\\NeuraL\\", std::env::var("APPDATA").unwrap_or_default()))
+    } else {
+        PathBuf::from(format!("{}/.config/NeuraL/", std::env::var("HOME").unwrap_or_default()))
+    }
+}
+
+pub fn get_default_settings_path() -> PathBuf {
+    get_default_config_directory().join(SETTINGS_FILE_NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalized_trims_url_and_empty_auth() {
+        let config = BackendConfig {
+            base_url: "  http://192.168.1.20:11434/ ".to_string(),
+            auth_header: Some("   ".to_string()),
+            timeout_secs: 30,
+            max_concurrent_requests: 1,
+            ..BackendConfig::default()
+        };
+
+        let normalized = config.normalized().unwrap();
+        assert_eq!(normalized.base_url, "http://192.168.1.20:11434");
+        assert_eq!(normalized.auth_header, None);
+    }
+
+    #[test]
+    fn test_normalized_rejects_invalid_input() {
+        let bad_scheme = BackendConfig {
+            base_url: "localhost:11434".to_string(),
+            ..BackendConfig::default()
+        };
+        assert!(bad_scheme.normalized().is_err(), "URL without scheme should be rejected");
+
+        let zero_timeout = BackendConfig {
+            timeout_secs: 0,
+            ..BackendConfig::default()
+        };
+        assert!(zero_timeout.normalized().is_err(), "Zero timeout should be rejected");
+
+        let zero_concurrency = BackendConfig {
+            max_concurrent_requests: 0,
+            ..BackendConfig::default()
+        };
+        assert!(zero_concurrency.normalized().is_err(), "Zero concurrency limit should be rejected");
+
+        let inverted_backoff = BackendConfig {
+            retry: RetryPolicy { initial_backoff_ms: 1_000, max_backoff_ms: 10, ..RetryPolicy::default() },
+            ..BackendConfig::default()
+        };
+        assert!(inverted_backoff.normalized().is_err(), "Initial backoff above the maximum should be rejected");
+
+        let success_status = BackendConfig {
+            retry: RetryPolicy { retryable_statuses: vec![200], ..RetryPolicy::default() },
+            ..BackendConfig::default()
+        };
+        assert!(success_status.normalized().is_err(), "Non-error statuses should be rejected");
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles_up_to_cap() {
+        let policy = RetryPolicy { initial_backoff_ms: 500, max_backoff_ms: 3_000, ..RetryPolicy::default() };
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(500));
+        assert_eq!(policy.backoff(1), Duration::from_millis(1_000));
+        assert_eq!(policy.backoff(2), Duration::from_millis(2_000));
+        assert_eq!(policy.backoff(3), Duration::from_millis(3_000));
+        assert_eq!(policy.backoff(80), Duration::from_millis(3_000));
+    }
+
+    #[test]
+    fn test_openai_config_normalized() {
+        let config = OpenAiConfig {
+            base_url: " http://localhost:1234/v1/ ".to_string(),
+            api_key: Some("".to_string()),
+            model: " qwen2.5-7b-instruct ".to_string(),
+            timeout_secs: 30,
+        };
+
+        let normalized = config.normalized().unwrap();
+        assert_eq!(normalized.base_url, "http://localhost:1234/v1");
+        assert_eq!(normalized.api_key, None);
+        assert_eq!(normalized.model, "qwen2.5-7b-instruct");
+
+        let bad_scheme = OpenAiConfig {
+            base_url: "localhost:8080/v1".to_string(),
+            ..OpenAiConfig::default()
+        };
+        assert!(bad_scheme.normalized().is_err(), "URL without scheme should be rejected");
+    }
+
+    #[test]
+    fn test_model_candidates_order() {
+        let preferences = ModelPreferences {
+            preferred_model: Some("llama3.1:8b".to_string()),
+            fallback_models: vec!["aya:8b".to_string(), "llama3.1:8b".to_string(), "qwen2.5:3b".to_string()],
+        };
+
+        assert_eq!(
+            preferences.candidates(None),
+            vec!["llama3.1:8b", "aya:8b", "qwen2.5:3b"],
+            "Preferred model should come first without duplicates"
+        );
+        assert_eq!(
+            preferences.candidates(Some("gemma3:3b")),
+            vec!["gemma3:3b"],
+            "Explicit model should bypass the fallback list"
+        );
+    }
+
+    #[test]
+    fn test_model_preferences_normalized() {
+        let preferences = ModelPreferences {
+            preferred_model: Some("  ".to_string()),
+            fallback_models: vec![" aya:8b ".to_string(), "".to_string(), "aya:8b".to_string()],
+        };
+
+        let normalized = preferences.normalized();
+        assert_eq!(normalized.preferred_model, None);
+        assert_eq!(normalized.fallback_models, vec!["aya:8b"]);
+    }
+
+    #[test]
+    fn test_direction_target_skips_the_source() {
+        let preferences = DirectionPreferences::default();
+        assert_eq!(preferences.target_for(Language::Japanese), Some(Language::English));
+        assert_eq!(preferences.target_for(Language::English), Some(Language::Japanese));
+        assert_eq!(preferences.target_for(Language::French), Some(Language::Japanese));
+    }
+
+    #[test]
+    fn test_direction_preferences_normalized() {
+        let preferences = DirectionPreferences {
+            languages: vec![Language::Japanese, Language::Japanese, Language::English],
+        };
+        assert_eq!(preferences.normalized().unwrap().languages, vec![Language::Japanese, Language::English]);
+
+        let single = DirectionPreferences { languages: vec![Language::English, Language::English] };
+        assert_eq!(single.normalized().unwrap_err().code(), "invalid_input");
+
+        // Files written before languages were tags store English names
+        let saved: DirectionPreferences = serde_json::from_str(r#"{"languages": ["Japanese", "en-US", "zh-Hant"]}"#).unwrap();
+        assert_eq!(saved.languages, vec![Language::Japanese, Language::English, Language::TraditionalChinese]);
+        assert!(serde_json::from_str::<DirectionPreferences>(r#"{"languages": ["Klingon"]}"#).is_err());
+    }
+
+    #[test]
+    fn test_settings_round_trip_and_missing_fields() {
+        let path = std::env::temp_dir().join(format!("neural_settings_{}.json", uuid::Uuid::new_v4()));
+
+        let settings = AppSettings {
+            default_engine: EngineKind::OpenAi,
+            backend: BackendConfig {
+                base_url: "https://ollama.internal".to_string(),
+                auth_header: Some("Bearer secret".to_string()),
+                timeout_secs: 45,
+                connect_timeout_secs: 3,
+                max_concurrent_requests: 4,
+                retry: RetryPolicy { max_retries: 0, ..RetryPolicy::default() },
+            },
+            openai: OpenAiConfig {
+                base_url: "http://localhost:1234/v1".to_string(),
+                api_key: None,
+                model: "qwen2.5-7b-instruct".to_string(),
+                timeout_secs: 60,
+            },
+            libretranslate: LibreTranslateConfig {
+                base_url: "http://mt.internal:5000".to_string(),
+                api_key: Some("lt-key".to_string()),
+                timeout_secs: 10,
+            },
+            models: ModelPreferences {
+                preferred_model: Some("qwen2.5:3b".to_string()),
+                fallback_models: vec!["aya:8b".to_string()],
+            },
+            inference: InferenceSettings {
+                default_profile: InferenceProfile::for_machine(Some(4), 8 * GIB),
+                model_profiles: BTreeMap::from([
+                    ("aya:8b".to_string(), InferenceProfile { num_ctx: 8192, ..InferenceProfile::for_machine(Some(4), 8 * GIB) }),
+                ]),
+            },
+            direction: DirectionPreferences {
+                languages: vec![Language::English, Language::Spanish],
+            },
+        };
+        settings.save_to(&path).unwrap();
+
+        let loaded = AppSettings::load_from(&path).unwrap();
+        assert_eq!(loaded.default_engine, EngineKind::OpenAi);
+        assert_eq!(loaded.backend, settings.backend);
+        assert_eq!(loaded.openai, settings.openai);
+        assert_eq!(loaded.libretranslate, settings.libretranslate);
+        assert_eq!(loaded.models, settings.models);
+        assert_eq!(loaded.inference, settings.inference);
+        assert_eq!(loaded.direction, settings.direction);
+
+        // Older or hand-edited files may omit fields entirely
+        fs::write(&path, r#"{"backend": {"base_url": "http://10.0.0.5:11434"}}"#).unwrap();
+        let partial = AppSettings::load_from(&path).unwrap();
+        assert_eq!(partial.backend.base_url, "http://10.0.0.5:11434");
+        assert_eq!(partial.backend.timeout_secs, DEFAULT_TIMEOUT_SECS);
+        assert_eq!(partial.backend.max_concurrent_requests, DEFAULT_MAX_CONCURRENT_REQUESTS);
+        assert_eq!(partial.models, ModelPreferences::default());
+        assert_eq!(partial.default_engine, EngineKind::Ollama);
+        assert_eq!(partial.openai, OpenAiConfig::default());
+        assert_eq!(partial.libretranslate, LibreTranslateConfig::default());
+        assert!(partial.inference.model_profiles.is_empty());
+        assert_eq!(partial.direction, DirectionPreferences::default());
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_inference_profile_for_machine() {
+        let small_mac = InferenceProfile::for_machine(Some(8), 8 * GIB);
+        assert_eq!(small_mac.num_thread, Some(8));
+        assert!(!small_mac.use_mlock, "mlock should stay off on 8 GB machines");
+        assert_eq!(small_mac.num_gpu, None);
+
+        let workstation = InferenceProfile::for_machine(None, 64 * GIB);
+        assert_eq!(workstation.num_thread, None);
+        assert!(workstation.use_mlock);
+        assert_eq!(workstation.num_ctx, 8192);
+    }
+
+    #[test]
+    fn test_inference_profile_normalized() {
+        let profile = InferenceProfile::for_machine(Some(4), 16 * GIB);
+        assert_eq!(InferenceProfile { num_gpu: Some(-1), ..profile.clone() }.normalized().unwrap().num_gpu, None);
+        assert!(InferenceProfile { num_thread: Some(0), ..profile.clone() }.normalized().is_err());
+        assert!(InferenceProfile { top_p: 0.0, ..profile.clone() }.normalized().is_err());
+        assert!(InferenceProfile { temperature: 3.0, ..profile.clone() }.normalized().is_err());
+        assert!(InferenceProfile { num_ctx: 128, ..profile }.normalized().is_err());
+    }
+
+    #[test]
+    fn test_profile_for_model_falls_back_to_default() {
+        let default_profile = InferenceProfile::for_machine(Some(4), 16 * GIB);
+        let cpu_only = InferenceProfile { num_gpu: Some(0), ..default_profile.clone() };
+        let settings = InferenceSettings {
+            default_profile: default_profile.clone(),
+            model_profiles: BTreeMap::from([("phi4-mini".to_string(), cpu_only.clone())]),
+        };
+
+        assert_eq!(settings.profile_for("phi4-mini"), &cpu_only);
+        assert_eq!(settings.profile_for("phi4-mini:latest"), &cpu_only);
+        assert_eq!(settings.profile_for("aya:8b"), &default_profile);
+    }
+}
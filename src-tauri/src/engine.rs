@@ -0,0 +1,218 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+use crate::error::NeuralError;
+use crate::langdetect::{self, LanguageCandidate};
+use crate::language::Language;
+use crate::libretranslate::SharedLibreTranslateClient;
+use crate::ollama::SharedOllamaClient;
+use crate::openai::SharedOpenAiClient;
+use crate::prompts::PromptStyle;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TranslateRequest {
+    pub text: String,
+    pub from_lang: Language,
+    pub to_lang: Language,
+    #[serde(default)]
+    pub models: Vec<String>, // Candidate models, tried in order (ignored by engines without models)
+    #[serde(default)]
+    pub style: PromptStyle,
+    #[serde(default)]
+    pub context: Option<TranslationContext>, // Preceding chunk of the same document, when translating in chunks
+}
+
+/// A chunk translated just before the current one, shown to LLM engines so terminology stays consistent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationContext {
+    pub source: String,
+    pub translation: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TranslateResponse {
+    pub translated_text: String,
+    #[serde(default)]
+    pub model: String, // Model that actually produced the output
+    #[serde(default)]
+    pub engine: String, // Engine that handled the request, as recorded in history
+    #[serde(default)]
+    pub request_id: Option<String>,
+    #[serde(default)]
+    pub direction: Option<TranslationDirection>, // Set for whole translations, once `auto` is resolved
+}
+
+/// Languages a translation actually went between
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TranslationDirection {
+    pub from_lang: Language,
+    pub to_lang: Language,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DetectLanguageRequest {
+    pub text: String,
+    #[serde(default)]
+    pub top_k: Option<usize>, // Candidates to return; DEFAULT_TOP_K when unset
+}
+
+/// Candidates returned when a detection request doesn't say how many it wants
+pub const DEFAULT_TOP_K: usize = 3;
+
+impl DetectLanguageRequest {
+    pub fn top_k(&self) -> usize {
+        self.top_k.unwrap_or(DEFAULT_TOP_K).max(1)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DetectLanguageResponse {
+    pub language: Language, // Best candidate
+    #[serde(default)]
+    pub confidence: f64,
+    #[serde(default)]
+    pub candidates: Vec<LanguageCandidate>, // Best first, `language` included
+}
+
+impl DetectLanguageResponse {
+    /// Response led by the first candidate; text without letters falls back to English with no confidence
+    pub fn from_candidates(candidates: Vec<LanguageCandidate>) -> Self {
+        let (language, confidence) = candidates
+            .first()
+            .map_or((Language::English, 0.0), |best| (best.language, best.confidence));
+        Self { language, confidence, candidates }
+    }
+}
+
+/// Why an engine is or isn't ready, returned by `check_health` in place of a bare bool
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HealthReport {
+    pub engine: String,
+    pub healthy: bool,   // Reachable and able to translate right now
+    pub reachable: bool, // The server answered at all
+    pub version: Option<String>,
+    pub installed_models: Vec<String>, // Recommended models that are ready to use
+    pub missing_models: Vec<MissingModel>,
+    pub probe_latency_ms: Option<u64>, // Round trip of a one-token generation, when one was attempted
+    pub error: Option<NeuralError>,    // What kept the engine from being healthy
+}
+
+/// A recommended model that isn't installed, with the command that installs it
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct MissingModel {
+    pub name: String,
+    pub pull_command: String,
+}
+
+impl HealthReport {
+    /// Report for a server that could not be reached
+    pub fn unreachable(engine: &str, error: NeuralError) -> Self {
+        Self {
+            engine: engine.to_string(),
+            error: Some(error),
+            ..Self::default()
+        }
+    }
+}
+
+// ===== Engine Abstraction =====
+
+/// Receives each translated fragment as a streaming engine produces it
+pub type ChunkSink<'a> = dyn FnMut(&str) + Send + 'a;
+
+/// A translation backend. Tauri commands talk to engines only through this trait,
+/// so adding a backend means implementing it and registering a new `EngineKind`.
+#[async_trait]
+pub trait TranslationEngine: Send + Sync {
+    /// Identifier recorded in `TranslationHistory.engine`
+    fn name(&self) -> &'static str;
+
+    async fn translate(&self, request: TranslateRequest) -> Result<TranslateResponse, NeuralError>;
+
+    /// Engines that can't stream deliver the whole translation as a single chunk
+    async fn translate_stream(
+        &self,
+        request: TranslateRequest,
+        on_chunk: &mut ChunkSink<'_>,
+    ) -> Result<TranslateResponse, NeuralError> {
+        let response = self.translate(request).await?;
+        on_chunk(&response.translated_text);
+        Ok(response)
+    }
+
+    async fn detect_language(&self, request: DetectLanguageRequest) -> Result<DetectLanguageResponse, NeuralError>;
+
+    /// Never fails: problems are described in the report instead
+    async fn check_health(&self) -> HealthReport;
+
+    /// Model names this engine can currently serve
+    async fn list_models(&self) -> Result<Vec<String>, NeuralError>;
+}
+
+/// Offline statistical detection shared by engines without a detection endpoint
+pub fn detect_language_locally(request: &DetectLanguageRequest) -> DetectLanguageResponse {
+    DetectLanguageResponse::from_candidates(langdetect::detect(&request.text, request.top_k()))
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EngineKind {
+    #[default]
+    Ollama,
+    OpenAi, // OpenAI-compatible chat completions: llama-server, LM Studio, vLLM
+    LibreTranslate, // Self-hosted machine translation server
+}
+
+/// Holds an engine's active client, swappable at runtime when its configuration changes
+pub struct SharedEngine<T> {
+    current: RwLock<Arc<T>>,
+}
+
+impl<T> SharedEngine<T> {
+    pub fn new(client: T) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(client)),
+        }
+    }
+
+    /// Snapshot of the active client; requests already running keep using the client they started with
+    pub fn current(&self) -> Arc<T> {
+        match self.current.read() {
+            Ok(client) => Arc::clone(&client),
+            Err(poisoned) => Arc::clone(&poisoned.into_inner()),
+        }
+    }
+
+    pub fn replace(&self, client: T) {
+        match self.current.write() {
+            Ok(mut current) => *current = Arc::new(client),
+            Err(poisoned) => *poisoned.into_inner() = Arc::new(client),
+        }
+    }
+}
+
+/// Managed Tauri state resolving an `EngineKind` to the live engine instance
+pub struct Engines {
+    ollama: Arc<SharedOllamaClient>,
+    openai: Arc<SharedOpenAiClient>,
+    libretranslate: Arc<SharedLibreTranslateClient>,
+}
+
+impl Engines {
+    pub fn new(
+        ollama: Arc<SharedOllamaClient>,
+        openai: Arc<SharedOpenAiClient>,
+        libretranslate: Arc<SharedLibreTranslateClient>,
+    ) -> Self {
+        Self { ollama, openai, libretranslate }
+    }
+
+    pub fn get(&self, kind: EngineKind) -> Arc<dyn TranslationEngine> {
+        match kind {
+            EngineKind::Ollama => self.ollama.current(),
+            EngineKind::OpenAi => self.openai.current(),
+            EngineKind::LibreTranslate => self.libretranslate.current(),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+// ===== Command Errors =====
+
+/// Error returned by every command. Serializes as `{ "code": ..., "message": ... }`
+/// so the frontend can branch on `code` instead of matching message text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NeuralError {
+    ConnectionRefused { service: String, url: String },
+    ModelNotFound { model: String },
+    NoModelAvailable { models: Vec<String> }, // Every candidate was missing or failed
+    Timeout { service: String },
+    Api { service: String, status: u16, message: String }, // Non-success HTTP status
+    Network(String),      // Any other transport failure
+    Parse(String),        // A response or file that could not be decoded
+    Cancelled { request_id: String },
+    InvalidInput(String), // Rejected arguments or settings
+    Unsupported(String),  // The chosen engine can't do what was asked
+    FileNotFound { path: String },
+    UnsupportedFile { extension: String },
+    UnsupportedLanguage { language: String }, // Not a tag or name we know how to translate
+    NoText,               // A document yielded no extractable text
+    Io(String),
+    HistoryCorrupt(String),
+    Internal(String),     // A fault in the app itself rather than in the request or a service
+}
+
+impl NeuralError {
+    /// Stable machine-readable identifier sent to the frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ConnectionRefused { .. } => "connection_refused",
+            Self::ModelNotFound { .. } => "model_not_found",
+            Self::NoModelAvailable { .. } => "no_model_available",
+            Self::Timeout { .. } => "timeout",
+            Self::Api { .. } => "api_error",
+            Self::Network(_) => "network_error",
+            Self::Parse(_) => "parse_error",
+            Self::Cancelled { .. } => "cancelled",
+            Self::InvalidInput(_) => "invalid_input",
+            Self::Unsupported(_) => "unsupported",
+            Self::FileNotFound { .. } => "file_not_found",
+            Self::UnsupportedFile { .. } => "unsupported_file",
+            Self::UnsupportedLanguage { .. } => "unsupported_language",
+            Self::NoText => "no_text",
+            Self::Io(_) => "io_error",
+            Self::HistoryCorrupt(_) => "history_corrupt",
+            Self::Internal(_) => "internal_error",
+        }
+    }
+
+    /// Classify a failed HTTP request to `service` at `url`; `action` describes what was attempted
+    pub fn from_request(service: &str, url: &str, action: &str, error: reqwest::Error) -> Self {
+        if error.is_connect() {
+            Self::ConnectionRefused { service: service.to_string(), url: url.to_string() }
+        } else if error.is_timeout() {
+            Self::Timeout { service: service.to_string() }
+        } else if error.is_decode() {
+            Self::Parse(format!("Failed to {}: {}", action, error))
+        } else {
+            Self::Network(format!("Failed to {}: {}", action, error))
+        }
+    }
+}
+
+impl fmt::Display for NeuralError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConnectionRefused { service, url } => {
+                write!(f, "Cannot connect to {} server at {}. Please make sure it is running.", service, url)
+            }
+            Self::ModelNotFound { model } => write!(f, "Model {} is not installed", model),
+            Self::NoModelAvailable { models } if models.is_empty() => write!(f, "No translation model configured"),
+            Self::NoModelAvailable { models } => {
+                write!(f, "No suitable model available. Please install one of: {}", models.join(", "))
+            }
+            Self::Timeout { service } => write!(f, "{} did not respond in time", service),
+            Self::Api { service, status, message } => write!(f, "{} returned an error ({}): {}", service, status, message),
+            Self::Network(message)
+            | Self::Parse(message)
+            | Self::InvalidInput(message)
+            | Self::Unsupported(message)
+            | Self::Io(message)
+            | Self::Internal(message) => write!(f, "{}", message),
+            Self::Cancelled { request_id } => write!(f, "Request {} was cancelled", request_id),
+            Self::FileNotFound { path } => write!(f, "File not found: {}", path),
+            Self::UnsupportedFile { extension } => write!(f, "Unsupported file type: {}", extension),
+            Self::UnsupportedLanguage { language } if language.is_empty() => write!(f, "No language given"),
+            Self::UnsupportedLanguage { language } => write!(f, "Unsupported language: {}", language),
+            Self::NoText => write!(f, "Could not extract any text from the file"),
+            Self::HistoryCorrupt(message) => write!(f, "Translation history is corrupt: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for NeuralError {}
+
+impl Serialize for NeuralError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("NeuralError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_code_and_message() {
+        let error = NeuralError::ConnectionRefused {
+            service: "Ollama".to_string(),
+            url: "http://localhost:11434".to_string(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "connection_refused",
+                "message": "Cannot connect to Ollama server at http://localhost:11434. Please make sure it is running."
+            })
+        );
+        assert_eq!(serde_json::to_value(NeuralError::NoText).unwrap()["code"], "no_text");
+    }
+}
@@ -0,0 +1,202 @@
+//! In-process fake Ollama server for testing `OllamaClient` offline.
+//!
+//! Serves `/api/version`, `/api/tags`, `/api/ps`, `/api/chat`, `/api/generate`,
+//! `/api/pull` and `/api/delete` from a small in-memory model store. Chat and
+//! generate answers can be scripted per model; unscripted requests to an installed
+//! model get a canned reply, and requests to anything else get Ollama's 404.
+
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use crate::stub_server::{RecordedRequest, StubResponse, StubServer};
+
+pub const FAKE_VERSION: &str = "0.6.2";
+
+/// Scripted outcome of one chat or generate request
+#[derive(Debug, Clone)]
+pub enum Reply {
+    /// A complete answer; streaming requests receive it as a single chunk
+    Text(String),
+    /// Pieces of an answer, one NDJSON line each when streaming, joined otherwise
+    Chunks(Vec<String>),
+    /// A failed request with Ollama's `{"error": ...}` body
+    Status(u16, String),
+    /// A stream that starts normally and then reports an error line
+    StreamError(String),
+}
+
+#[derive(Default)]
+struct FakeState {
+    installed: Vec<String>,
+    loaded: Vec<String>,
+    scripts: HashMap<String, VecDeque<Reply>>,
+}
+
+pub struct FakeOllama {
+    server: StubServer,
+    state: Arc<Mutex<FakeState>>,
+}
+
+impl FakeOllama {
+    /// Start a server with `models` already installed
+    pub async fn start(models: &[&str]) -> Self {
+        let state = Arc::new(Mutex::new(FakeState {
+            installed: models.iter().map(|model| model.to_string()).collect(),
+            ..FakeState::default()
+        }));
+
+        let server = StubServer::start({
+            let state = Arc::clone(&state);
+            move |request| respond(&mut state.lock().unwrap(), request)
+        }).await;
+
+        Self { server, state }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.server.base_url
+    }
+
+    /// Queue replies for the next chat or generate requests to `model`, used in order
+    pub fn script(&self, model: &str, replies: Vec<Reply>) {
+        self.state.lock().unwrap()
+            .scripts
+            .entry(model.to_string())
+            .or_default()
+            .extend(replies);
+    }
+
+    /// Models currently held in memory, as `/api/ps` reports them
+    pub fn loaded(&self) -> Vec<String> {
+        self.state.lock().unwrap().loaded.clone()
+    }
+
+    pub fn installed(&self) -> Vec<String> {
+        self.state.lock().unwrap().installed.clone()
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.server.requests()
+    }
+
+    /// Model named by each request to `path`, in arrival order
+    pub fn models_requested(&self, path: &str) -> Vec<String> {
+        self.requests()
+            .iter()
+            .filter(|request| request.path == path)
+            .map(|request| request.json()["model"].as_str().unwrap_or_default().to_string())
+            .collect()
+    }
+}
+
+fn respond(state: &mut FakeState, request: &RecordedRequest) -> StubResponse {
+    match request.path.as_str() {
+        "/api/version" => StubResponse::json(200, json!({ "version": FAKE_VERSION })),
+        "/api/tags" => StubResponse::json(200, json!({
+            "models": state.installed.iter()
+                .map(|name| json!({ "name": name, "size": 1_000_000_000u64, "details": { "parameter_size": "3B" } }))
+                .collect::<Vec<_>>()
+        })),
+        "/api/ps" => StubResponse::json(200, json!({
+            "models": state.loaded.iter()
+                .map(|name| json!({ "name": name, "size": 1_000_000_000u64, "size_vram": 600_000_000u64 }))
+                .collect::<Vec<_>>()
+        })),
+        "/api/chat" => generate(state, &request.json(), "message"),
+        "/api/generate" => generate(state, &request.json(), "response"),
+        "/api/pull" => pull(state, &request.json()),
+        "/api/delete" => {
+            let model = request.json()["model"].as_str().unwrap_or_default().to_string();
+            match state.installed.iter().position(|name| *name == model) {
+                Some(index) => {
+                    state.installed.remove(index);
+                    state.loaded.retain(|name| *name != model);
+                    StubResponse::json(200, json!({}))
+                }
+                None => not_found(&model),
+            }
+        }
+        _ => StubResponse::text(404, "404 page not found"),
+    }
+}
+
+fn not_found(model: &str) -> StubResponse {
+    StubResponse::json(404, json!({ "error": format!("model '{}' not found", model) }))
+}
+
+/// Chat answers under `message.content`, generate under `response`
+fn generate(state: &mut FakeState, body: &serde_json::Value, field: &str) -> StubResponse {
+    let model = body["model"].as_str().unwrap_or_default().to_string();
+    if !state.installed.contains(&model) {
+        return not_found(&model);
+    }
+
+    // A generate request without a prompt only loads or unloads the model
+    let load_only = field == "response" && body.get("prompt").is_none();
+    if body["keep_alive"] == json!(0) {
+        state.loaded.retain(|name| *name != model);
+    } else if !state.loaded.contains(&model) {
+        state.loaded.push(model.clone());
+    }
+    if load_only {
+        return StubResponse::json(200, json!({ "model": model, "response": "", "done": true }));
+    }
+
+    let reply = state.scripts
+        .get_mut(&model)
+        .and_then(VecDeque::pop_front)
+        .unwrap_or_else(|| Reply::Text(format!("translated by {}", model)));
+
+    let line = |content: &str, done: bool| {
+        let mut line = json!({ "model": model, "done": done });
+        line[field] = if field == "message" {
+            json!({ "role": "assistant", "content": content })
+        } else {
+            json!(content)
+        };
+        line.to_string() + "\n"
+    };
+
+    let chunks = match reply {
+        Reply::Status(status, error) => return StubResponse::json(status, json!({ "error": error })),
+        Reply::StreamError(error) => {
+            return StubResponse::streamed("application/x-ndjson", vec![
+                line("", false),
+                json!({ "error": error }).to_string() + "\n",
+            ]);
+        }
+        Reply::Text(text) => vec![text],
+        Reply::Chunks(chunks) => chunks,
+    };
+
+    if body["stream"] == json!(false) {
+        let text = chunks.concat();
+        return StubResponse::json(200, serde_json::from_str(&line(&text, true)).unwrap());
+    }
+
+    let mut lines: Vec<String> = chunks.iter().map(|chunk| line(chunk, false)).collect();
+    lines.push(line("", true));
+    StubResponse::streamed("application/x-ndjson", lines)
+}
+
+/// Stream a short download and install the model, or fail for names containing "missing"
+fn pull(state: &mut FakeState, body: &serde_json::Value) -> StubResponse {
+    let model = body["model"].as_str().unwrap_or_default().to_string();
+    if model.contains("missing") {
+        return StubResponse::streamed("application/x-ndjson", vec![
+            json!({ "status": "pulling manifest" }).to_string() + "\n",
+            json!({ "error": "pull model manifest: file does not exist" }).to_string() + "\n",
+        ]);
+    }
+
+    if !state.installed.contains(&model) {
+        state.installed.push(model);
+    }
+    StubResponse::streamed("application/x-ndjson", vec![
+        json!({ "status": "pulling manifest" }).to_string() + "\n",
+        json!({ "status": "pulling 6a0746a1ec1a", "digest": "sha256:6a0746a1ec1a", "total": 200, "completed": 100 }).to_string() + "\n",
+        json!({ "status": "pulling 6a0746a1ec1a", "digest": "sha256:6a0746a1ec1a", "total": 200, "completed": 200 }).to_string() + "\n",
+        json!({ "status": "success" }).to_string() + "\n",
+    ])
+}
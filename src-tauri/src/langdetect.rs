@@ -0,0 +1,539 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::chunking::split_sentences;
+use crate::language::Language;
+
+// ===== Offline Language Detection =====
+
+/// Characters of input examined; longer documents are judged by their opening
+const MAX_SAMPLE_CHARS: usize = 2000;
+
+/// Character n-gram orders each script's profiles are built from. Han characters
+/// carry more information each, and bigrams already capture most compounds.
+const LATIN_ORDERS: &[usize] = &[1, 2, 3];
+const HAN_ORDERS: &[usize] = &[1, 2];
+
+/// Add-α smoothing so n-grams a profile never saw cost probability instead of ruling it out
+const SMOOTHING: f64 = 0.5;
+
+/// Prose runs lighter than this join a neighbour instead of getting their own span,
+/// so a loanword or a one-word reply doesn't split a sentence; see `run_weight`
+const MIN_SPAN_WEIGHT: usize = 12;
+
+/// Bundled training text for each Latin-script language
+const LATIN_CORPORA: [(Language, &str); 12] = [
+    (Language::English, include_str!("../resources/langdetect/en.txt")),
+    (Language::Spanish, include_str!("../resources/langdetect/es.txt")),
+    (Language::French, include_str!("../resources/langdetect/fr.txt")),
+    (Language::German, include_str!("../resources/langdetect/de.txt")),
+    (Language::Italian, include_str!("../resources/langdetect/it.txt")),
+    (Language::Portuguese, include_str!("../resources/langdetect/pt.txt")),
+    (Language::Dutch, include_str!("../resources/langdetect/nl.txt")),
+    (Language::Swedish, include_str!("../resources/langdetect/sv.txt")),
+    (Language::Polish, include_str!("../resources/langdetect/pl.txt")),
+    (Language::Turkish, include_str!("../resources/langdetect/tr.txt")),
+    (Language::Vietnamese, include_str!("../resources/langdetect/vi.txt")),
+    (Language::Indonesian, include_str!("../resources/langdetect/id.txt")),
+];
+
+/// Bundled text for the languages written in Han characters.
+/// Only the Han characters are profiled, so kanji-only Japanese stays distinguishable
+/// and Simplified and Traditional Chinese separate by character forms.
+const HAN_CORPORA: [(Language, &str); 3] = [
+    (Language::Japanese, include_str!("../resources/langdetect/ja.txt")),
+    (Language::SimplifiedChinese, include_str!("../resources/langdetect/zh-Hans.txt")),
+    (Language::TraditionalChinese, include_str!("../resources/langdetect/zh-Hant.txt")),
+];
+
+/// A possible language for the input and the share of confidence it received
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageCandidate {
+    pub language: Language,
+    pub confidence: f64, // 0.0-1.0; the candidates of one detection sum to at most 1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Devanagari,
+    Thai,
+    Hangul,
+    Kana,
+    Han,
+}
+
+impl Script {
+    /// Language assumed for scripts that are only profiled for one language
+    fn language(self) -> Option<Language> {
+        match self {
+            Self::Cyrillic => Some(Language::Russian),
+            Self::Greek => Some(Language::Greek),
+            Self::Arabic => Some(Language::Arabic),
+            Self::Hebrew => Some(Language::Hebrew),
+            Self::Devanagari => Some(Language::Hindi),
+            Self::Thai => Some(Language::Thai),
+            Self::Hangul => Some(Language::Korean),
+            Self::Kana => Some(Language::Japanese),
+            Self::Latin | Self::Han => None,
+        }
+    }
+}
+
+fn script_of(c: char) -> Option<Script> {
+    if !c.is_alphabetic() {
+        return None;
+    }
+    match c {
+        'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}' => Some(Script::Latin),
+        '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => Some(Script::Greek),
+        '\u{0400}'..='\u{04FF}' => Some(Script::Cyrillic),
+        '\u{0590}'..='\u{05FF}' => Some(Script::Hebrew),
+        '\u{0600}'..='\u{06FF}' | '\u{0750}'..='\u{077F}' => Some(Script::Arabic),
+        '\u{0900}'..='\u{097F}' => Some(Script::Devanagari),
+        '\u{0E00}'..='\u{0E7F}' => Some(Script::Thai),
+        '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7AF}' => Some(Script::Hangul),
+        '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' | '\u{FF66}'..='\u{FF9F}' => Some(Script::Kana),
+        '\u{3005}' | '\u{3007}' | '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}' => {
+            Some(Script::Han) // Iteration mark 々 and ideographic zero included
+        }
+        _ => None,
+    }
+}
+
+/// Most likely languages of `text`, best first, at most `top_k` of them.
+/// Each script's share of the letters is split between the languages written in it:
+/// Latin and Han text by character n-gram profiles, other scripts by the script itself.
+/// Returns nothing when the text contains no letters.
+pub fn detect(text: &str, top_k: usize) -> Vec<LanguageCandidate> {
+    let sample: String = text.chars().take(MAX_SAMPLE_CHARS).collect();
+
+    let mut letters: HashMap<Script, usize> = HashMap::new();
+    for script in sample.chars().filter_map(script_of) {
+        *letters.entry(script).or_default() += 1;
+    }
+    let total: usize = letters.values().sum();
+    if total == 0 {
+        return Vec::new();
+    }
+
+    let mut scores: HashMap<Language, f64> = HashMap::new();
+    for (&script, &count) in &letters {
+        let share = count as f64 / total as f64;
+        match script {
+            // Kanji next to kana is Japanese; on its own it could be Japanese or either Chinese
+            Script::Han if letters.contains_key(&Script::Kana) => *scores.entry(Language::Japanese).or_default() += share,
+            Script::Latin | Script::Han => {
+                let model = if script == Script::Latin { &*LATIN_MODEL } else { &*HAN_MODEL };
+                for (language, probability) in model.classify(&sample) {
+                    *scores.entry(language).or_default() += share * probability;
+                }
+            }
+            script => {
+                if let Some(language) = script.language() {
+                    *scores.entry(language).or_default() += share;
+                }
+            }
+        }
+    }
+
+    let mut candidates: Vec<LanguageCandidate> = scores
+        .into_iter()
+        .map(|(language, confidence)| LanguageCandidate { language, confidence })
+        .collect();
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence).then_with(|| a.language.cmp(&b.language)));
+    candidates.truncate(top_k.max(1));
+    candidates
+}
+
+// ===== Mixed-Language Segments =====
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpanKind {
+    Text,
+    Code, // Inline `code` or a fenced block, never translated
+    Url,
+}
+
+/// A stretch of input in one language; concatenating the spans of a text reproduces it exactly
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LanguageSpan {
+    pub text: String,
+    pub kind: SpanKind,
+    pub language: Option<Language>, // None for code, URLs and text without letters
+    pub confidence: f64,
+}
+
+/// Split `text` into spans of code, URLs and runs of prose, each prose run labelled with its
+/// most likely language. Prose is cut at sentence ends and script changes; runs too short to
+/// judge on their own join a neighbour, and neighbours in the same language are merged.
+pub fn detect_segments(text: &str) -> Vec<LanguageSpan> {
+    let mut spans: Vec<LanguageSpan> = Vec::new();
+    let mut cursor = 0;
+
+    for (range, kind) in protected_ranges(text) {
+        push_prose(&mut spans, &text[cursor..range.start]);
+        spans.push(LanguageSpan { text: text[range.clone()].to_string(), kind, language: None, confidence: 0.0 });
+        cursor = range.end;
+    }
+    push_prose(&mut spans, &text[cursor..]);
+
+    spans
+}
+
+fn push_prose(spans: &mut Vec<LanguageSpan>, prose: &str) {
+    for run in prose_runs(prose) {
+        let text = &prose[run.range];
+        let best = detect(&prose[run.core], 1).into_iter().next();
+        let (language, confidence) = best.map_or((None, 0.0), |best| (Some(best.language), best.confidence));
+
+        match spans.last_mut() {
+            Some(last) if last.kind == SpanKind::Text && last.language == language => {
+                // Average the confidences by how much text each side contributed
+                let (before, added) = (last.text.chars().count() as f64, text.chars().count() as f64);
+                last.confidence = (last.confidence * before + confidence * added) / (before + added);
+                last.text.push_str(text);
+            }
+            _ => spans.push(LanguageSpan { text: text.to_string(), kind: SpanKind::Text, language, confidence }),
+        }
+    }
+}
+
+/// Byte ranges of fenced code blocks, inline code and URLs, in order
+fn protected_ranges(text: &str) -> Vec<(Range<usize>, SpanKind)> {
+    let mut ranges = Vec::new();
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let found = if let Some(body) = rest.strip_prefix("```") {
+            // An unclosed fence runs to the end of the text
+            let end = body.find("```").map_or(text.len(), |close| i + 3 + close + 3);
+            Some((i..end, SpanKind::Code))
+        } else if let Some(body) = rest.strip_prefix('`') {
+            body.find(['`', '\n'])
+                .filter(|&close| body[close..].starts_with('`'))
+                .map(|close| (i..i + close + 2, SpanKind::Code))
+        } else if ["https://", "http://", "www."].iter().any(|scheme| rest.starts_with(scheme))
+            && !text[..i].ends_with(|c: char| c.is_ascii_alphanumeric())
+        {
+            let length = rest.find(|c: char| !c.is_ascii_graphic()).unwrap_or(rest.len());
+            let url = rest[..length].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '}', '\'', '"']);
+            Some((i..i + url.len(), SpanKind::Url))
+        } else {
+            None
+        };
+
+        match found {
+            Some((range, kind)) => {
+                i = range.end;
+                ranges.push((range, kind));
+            }
+            None => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+
+    ranges
+}
+
+/// A piece of prose to label, as byte ranges into it. Light neighbours folded into the run
+/// extend `range`, but only `core` is classified, so a loanword can't outvote its sentence.
+struct ProseRun {
+    range: Range<usize>,
+    core: Range<usize>,
+}
+
+/// Runs covering `prose` exactly, cut at sentence ends and script changes
+fn prose_runs(prose: &str) -> Vec<ProseRun> {
+    let mut pieces: Vec<Range<usize>> = Vec::new();
+    let mut offset = 0;
+
+    for sentence in split_sentences(prose) {
+        let mut start = offset;
+        let mut family = None;
+        for (i, c) in sentence.char_indices() {
+            let Some(script) = script_of(c) else { continue };
+            let next = script_family(script);
+            if family.is_some_and(|family| family != next) {
+                pieces.push(start..offset + i);
+                start = offset + i;
+            }
+            family = Some(next);
+        }
+        offset += sentence.len();
+        pieces.push(start..offset);
+    }
+
+    // Light pieces join the run before them; a light first run is taken over by the next heavy piece
+    let mut runs: Vec<ProseRun> = Vec::new();
+    for piece in pieces {
+        let heavy = run_weight(&prose[piece.clone()]) >= MIN_SPAN_WEIGHT;
+        match runs.last_mut() {
+            Some(last) if !heavy => last.range.end = piece.end,
+            Some(last) if run_weight(&prose[last.core.clone()]) < MIN_SPAN_WEIGHT => {
+                last.range.end = piece.end;
+                last.core = piece;
+            }
+            _ => runs.push(ProseRun { range: piece.clone(), core: piece }),
+        }
+    }
+    runs
+}
+
+/// Kanji and kana alternate within Japanese sentences, so they count as one family
+fn script_family(script: Script) -> Script {
+    match script {
+        Script::Kana => Script::Han,
+        script => script,
+    }
+}
+
+/// Letters in `text`, counting CJK characters and Hangul syllables as three since each
+/// carries about as much as a few Latin letters
+fn run_weight(text: &str) -> usize {
+    text.chars()
+        .filter_map(script_of)
+        .map(|script| match script {
+            Script::Han | Script::Kana | Script::Hangul => 3,
+            _ => 1,
+        })
+        .sum()
+}
+
+// ===== Character N-gram Profiles =====
+
+/// Frequencies of each n-gram order in one language's training text
+struct Profile {
+    language: Language,
+    counts: Vec<HashMap<String, u32>>, // Indexed like the model's orders
+    totals: Vec<u32>,
+}
+
+/// Naive Bayes classifier over the languages written in one script
+struct NgramModel {
+    script: Script,
+    orders: &'static [usize],
+    profiles: Vec<Profile>,
+    vocabulary: Vec<usize>, // Distinct n-grams per order across all profiles, for smoothing
+}
+
+static LATIN_MODEL: Lazy<NgramModel> = Lazy::new(|| NgramModel::train(Script::Latin, LATIN_ORDERS, &LATIN_CORPORA));
+static HAN_MODEL: Lazy<NgramModel> = Lazy::new(|| NgramModel::train(Script::Han, HAN_ORDERS, &HAN_CORPORA));
+
+impl NgramModel {
+    fn train(script: Script, orders: &'static [usize], corpora: &[(Language, &str)]) -> Self {
+        let profiles: Vec<Profile> = corpora
+            .iter()
+            .map(|&(language, text)| {
+                let mut counts = vec![HashMap::new(); orders.len()];
+                for_each_ngram(text, script, orders, |order, gram| {
+                    *counts[order].entry(gram.to_string()).or_insert(0) += 1
+                });
+                let totals = counts.iter().map(|grams| grams.values().sum()).collect();
+                Profile { language, counts, totals }
+            })
+            .collect();
+
+        let vocabulary = (0..orders.len())
+            .map(|order| {
+                let mut grams: Vec<&String> = profiles.iter().flat_map(|profile| profile.counts[order].keys()).collect();
+                grams.sort_unstable();
+                grams.dedup();
+                grams.len() + 1 // One more for n-grams no profile has seen
+            })
+            .collect();
+
+        Self { script, orders, profiles, vocabulary }
+    }
+
+    /// Posterior of each profiled language, judged only by the letters of this model's script
+    fn classify(&self, text: &str) -> Vec<(Language, f64)> {
+        let mut log_likelihoods = vec![0.0; self.profiles.len()];
+        for_each_ngram(text, self.script, self.orders, |order, gram| {
+            for (profile, score) in self.profiles.iter().zip(log_likelihoods.iter_mut()) {
+                let count = profile.counts[order].get(gram).copied().unwrap_or(0) as f64;
+                let total = profile.totals[order] as f64;
+                *score += ((count + SMOOTHING) / (total + SMOOTHING * self.vocabulary[order] as f64)).ln();
+            }
+        });
+
+        // Every character takes part in one n-gram per order, so the orders are far from
+        // independent evidence; averaging them keeps the posterior from being overconfident
+        let damped: Vec<f64> = log_likelihoods.iter().map(|score| score / self.orders.len() as f64).collect();
+        let best = damped.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<f64> = damped.iter().map(|score| (score - best).exp()).collect();
+        let sum: f64 = weights.iter().sum();
+
+        self.profiles
+            .iter()
+            .zip(weights)
+            .map(|(profile, weight)| (profile.language, weight / sum))
+            .collect()
+    }
+}
+
+/// Call `f` with the index of each order and every n-gram of that order, taken from each run
+/// of `script` letters in `text`. Runs are lowercased and padded with a space on both sides
+/// so word starts and endings count as features.
+fn for_each_ngram(text: &str, script: Script, orders: &[usize], mut f: impl FnMut(usize, &str)) {
+    for word in text.split(|c| script_of(c) != Some(script)).filter(|word| !word.is_empty()) {
+        let padded: Vec<char> = format!(" {} ", word.to_lowercase()).chars().collect();
+        let mut gram = String::new();
+        for (order, &n) in orders.iter().enumerate() {
+            for window in padded.windows(n) {
+                if n == 1 && window[0] == ' ' {
+                    continue;
+                }
+                gram.clear();
+                gram.extend(window);
+                f(order, &gram);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Labelled sentences kept apart from the training text
+    const TEST_CORPUS: &str = include_str!("../resources/langdetect/test_corpus.tsv");
+
+    fn labelled() -> Vec<(&'static str, &'static str)> {
+        TEST_CORPUS
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.split_once('\t').expect("corpus lines are <language>\\t<text>"))
+            .collect()
+    }
+
+    #[test]
+    fn test_labelled_corpus_is_detected() {
+        let corpus = labelled();
+        let misses: Vec<String> = corpus
+            .iter()
+            .filter_map(|&(expected, text)| {
+                let detected = detect(text, 1);
+                let language = detected.first().map(|candidate| candidate.language.tag());
+                (language != Some(expected)).then(|| format!("{} detected as {:?}: {}", expected, language, text))
+            })
+            .collect();
+
+        let accuracy = 1.0 - misses.len() as f64 / corpus.len() as f64;
+        assert!(accuracy >= 0.95, "Accuracy {:.2} below 0.95:\n{}", accuracy, misses.join("\n"));
+    }
+
+    #[test]
+    fn test_every_supported_language_is_covered() {
+        let languages: Vec<&str> = labelled().iter().map(|&(language, _)| language).collect();
+        for language in ["ja", "en", "zh-CN", "zh-TW", "ko", "es", "fr", "de"] {
+            assert!(languages.contains(&language), "No labelled sentences for {}", language);
+        }
+    }
+
+    #[test]
+    fn test_candidates_are_ranked_and_bounded() {
+        let candidates = detect("Je voudrais réserver une table pour deux personnes ce soir.", 3);
+
+        assert_eq!(candidates.len(), 3);
+        assert_eq!(candidates[0].language, Language::French);
+        assert!(candidates[0].confidence > 0.8, "Unexpected confidence: {:?}", candidates);
+        assert!(candidates.windows(2).all(|pair| pair[0].confidence >= pair[1].confidence));
+        assert!(candidates.iter().map(|candidate| candidate.confidence).sum::<f64>() <= 1.0 + 1e-9);
+    }
+
+    #[test]
+    fn test_short_text_is_less_certain_than_long_text() {
+        let short = detect("casa", 1)[0].confidence;
+        let long = detect("La casa de mis abuelos tiene un jardín muy grande con árboles frutales.", 1)[0].confidence;
+        assert!(short < long, "short {} vs long {}", short, long);
+    }
+
+    #[test]
+    fn test_script_detection() {
+        assert_eq!(detect("こんにちは世界", 1)[0].language, Language::Japanese);
+        assert_eq!(detect("我们在学习中文", 1)[0].language, Language::SimplifiedChinese);
+        assert_eq!(detect("我們在學習中文", 1)[0].language, Language::TraditionalChinese);
+        assert_eq!(detect("안녕하세요", 1)[0].language, Language::Korean);
+        assert_eq!(detect("Привет, как дела?", 1)[0].language, Language::Russian);
+        assert_eq!(detect("こんにちは", 1)[0].confidence, 1.0);
+    }
+
+    #[test]
+    fn test_kanji_only_text_is_told_apart() {
+        assert_eq!(detect("国際会議開催予定", 1)[0].language, Language::Japanese);
+        assert_eq!(detect("国际会议将在北京举行", 1)[0].language, Language::SimplifiedChinese);
+        assert_eq!(detect("國際會議將在臺北舉行", 1)[0].language, Language::TraditionalChinese);
+    }
+
+    #[test]
+    fn test_mixed_scripts_split_confidence() {
+        let candidates = detect("Reactのコンポーネント", 2);
+        let languages: Vec<&str> = candidates.iter().map(|candidate| candidate.language.tag()).collect();
+
+        assert_eq!(languages[0], "ja");
+        assert!(candidates[0].confidence < 1.0);
+    }
+
+    fn summarize(spans: &[LanguageSpan]) -> Vec<(SpanKind, Option<&str>, &str)> {
+        spans.iter().map(|span| (span.kind, span.language.map(Language::tag), span.text.as_str())).collect()
+    }
+
+    #[test]
+    fn test_segments_split_mixed_chat_message() {
+        let text = "明日のmeetingは10時からです。Please review the pull request before then.";
+        let spans = detect_segments(text);
+
+        assert_eq!(summarize(&spans), vec![
+            (SpanKind::Text, Some("ja"), "明日のmeetingは10時からです。"),
+            (SpanKind::Text, Some("en"), "Please review the pull request before then."),
+        ]);
+        assert!(spans.iter().all(|span| span.confidence > 0.5), "Unexpected spans: {:?}", spans);
+    }
+
+    #[test]
+    fn test_segments_leave_code_and_urls_alone() {
+        let text = "詳細は https://example.com/docs?id=1. を参照し、`cargo test` を実行してください。";
+        let spans = detect_segments(text);
+
+        assert_eq!(summarize(&spans), vec![
+            (SpanKind::Text, Some("ja"), "詳細は "),
+            (SpanKind::Url, None, "https://example.com/docs?id=1"),
+            (SpanKind::Text, Some("ja"), ". を参照し、"),
+            (SpanKind::Code, None, "`cargo test`"),
+            (SpanKind::Text, Some("ja"), " を実行してください。"),
+        ]);
+        assert_eq!(spans.iter().map(|span| span.text.as_str()).collect::<String>(), text);
+    }
+
+    #[test]
+    fn test_segments_keep_fenced_blocks_whole() {
+        let text = "Could you check why this fails?\n```\nlet total = items.len();\n```\nエラーは出ていません。";
+        let spans = detect_segments(text);
+
+        assert_eq!(summarize(&spans), vec![
+            (SpanKind::Text, Some("en"), "Could you check why this fails?\n"),
+            (SpanKind::Code, None, "```\nlet total = items.len();\n```"),
+            (SpanKind::Text, Some("ja"), "\nエラーは出ていません。"),
+        ]);
+    }
+
+    #[test]
+    fn test_segments_without_letters() {
+        assert!(detect_segments("").is_empty());
+        assert_eq!(summarize(&detect_segments("12:30 - 13:00")), vec![(SpanKind::Text, None, "12:30 - 13:00")]);
+    }
+
+    #[test]
+    fn test_no_letters_detects_nothing() {
+        assert!(detect("", 3).is_empty());
+        assert!(detect("12:30 - 4,000 !!", 3).is_empty());
+    }
+}
@@ -1,6 +1,32 @@
+mod cancellation;
+mod chunking;
+mod config;
+mod engine;
+mod error;
+#[cfg(test)]
+mod fake_ollama;
+mod langdetect;
+mod language;
+mod libretranslate;
 mod ollama;
+mod openai;
+mod pipeline;
+mod prompts;
+mod streaming;
+#[cfg(test)]
+mod stub_server;
 
-use ollama::{OllamaClient, TranslateRequest, TranslateResponse, DetectLanguageRequest, DetectLanguageResponse};
+use cancellation::{resolve_request_id, RequestRegistry};
+use config::{AppSettings, BackendConfig, DirectionPreferences, InferenceProfile, InferenceSettings, LibreTranslateConfig, ModelPreferences, OpenAiConfig};
+use error::NeuralError;
+use engine::{Engines, EngineKind, HealthReport, TranslationEngine, TranslateResponse, DetectLanguageRequest, DetectLanguageResponse};
+use langdetect::LanguageSpan;
+use language::{Language, LanguageChoice, LanguageInfo};
+use ollama::{model_name_matches, InstalledModel, OllamaClient, SharedOllamaClient, ModelCatalog};
+use libretranslate::{LibreTranslateClient, SharedLibreTranslateClient};
+use openai::{OpenAiClient, SharedOpenAiClient};
+use pipeline::{translate_chunked, ChunkProgress, PendingTranslation, CHUNK_TOKEN_BUDGET};
+use prompts::PromptStyle;
 use tauri::{State, Manager, AppHandle, Emitter};
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -56,8 +82,10 @@ pub struct TranslationHistory {
     pub translated_text: String,
     pub from_language: String,
     pub to_language: String,
-    pub engine: String, // "ollama" or "ml"
+    pub engine: String, // "ollama", "openai", "libretranslate" or "ml"
     pub latency_ms: Option<u32>,
+    #[serde(default)]
+    pub model: Option<String>, // Model that produced the translation, when known
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -68,146 +96,693 @@ pub struct HistoryFile {
     pub translations: Vec<TranslationHistory>,
 }
 
+// ===== Streaming Translation Events =====
+
+#[derive(Debug, Serialize, Clone)]
+struct TranslationChunkEvent {
+    request_id: String,
+    delta: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct TranslationProgressEvent {
+    request_id: String,
+    completed_chunks: usize,
+    total_chunks: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct TranslationDoneEvent {
+    request_id: String,
+    translated_text: Option<String>,
+    error: Option<NeuralError>,
+}
+
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+/// Emit `translation-progress` after each chunk of a long document
+fn emit_progress(app: &AppHandle, request_id: &str, progress: ChunkProgress) {
+    let event = TranslationProgressEvent {
+        request_id: request_id.to_string(),
+        completed_chunks: progress.completed_chunks,
+        total_chunks: progress.total_chunks,
+    };
+    if let Err(e) = app.emit("translation-progress", event) {
+        tracing::error!("Failed to emit translation-progress event: {}", e);
+    }
+}
+
+/// Run a chunked translation on the selected engine under `request_id` so `cancel_translation` can abort it.
+/// `auto` languages are resolved first, inside the same cancellable request.
+async fn run_translation(
+    engine: Arc<dyn TranslationEngine>,
+    pending: PendingTranslation,
+    direction: DirectionPreferences,
+    request_id: String,
+    requests: &RequestRegistry,
+    app: &AppHandle,
+) -> Result<TranslateResponse, NeuralError> {
+    let mut on_progress = |progress: ChunkProgress| emit_progress(app, &request_id, progress);
+    let (engine, on_progress) = (engine.as_ref(), &mut on_progress);
+    let translation = async move {
+        let request = pending.resolve(engine, &direction).await?;
+        translate_chunked(engine, request, CHUNK_TOKEN_BUDGET, None, on_progress).await
+    };
+    
+    let mut response = requests.run(&request_id, translation).await?;
+    response.request_id = Some(request_id);
+    Ok(response)
+}
+
+/// The engine a command should use: the explicit choice, or the default saved in settings
+async fn resolve_engine_kind(engine: Option<EngineKind>, settings: &Mutex<AppSettings>) -> EngineKind {
+    match engine {
+        Some(kind) => kind,
+        None => settings.lock().await.default_engine,
+    }
+}
+
+/// Models to try: Ollama walks the saved preference list, OpenAI-compatible servers
+/// use their configured model unless one is named explicitly, and MT servers have none
+async fn candidate_models(
+    kind: EngineKind,
+    model: Option<&str>,
+    settings: &Mutex<AppSettings>,
+) -> Vec<String> {
+    match kind {
+        EngineKind::Ollama => settings.lock().await.models.candidates(model),
+        EngineKind::OpenAi => model
+            .map(str::trim)
+            .filter(|model| !model.is_empty())
+            .map(|model| vec![model.to_string()])
+            .unwrap_or_default(),
+        EngineKind::LibreTranslate => Vec::new(),
+    }
+}
+
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn translate(
     text: String,
     from_lang: String,
     to_lang: String,
-    state: State<'_, Arc<Mutex<OllamaClient>>>,
-) -> Result<TranslateResponse, String> {
-    let client = state.lock().await;
-    let request = TranslateRequest {
+    model: Option<String>,
+    engine: Option<EngineKind>,
+    request_id: Option<String>,
+    app: AppHandle,
+    engines: State<'_, Arc<Engines>>,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+    requests: State<'_, Arc<RequestRegistry>>,
+) -> Result<TranslateResponse, NeuralError> {
+    let kind = resolve_engine_kind(engine, &settings).await;
+    let engine = engines.get(kind);
+    let pending = PendingTranslation {
         text,
-        from_lang,
-        to_lang,
+        from_lang: LanguageChoice::parse(&from_lang)?,
+        to_lang: LanguageChoice::parse(&to_lang)?,
+        models: candidate_models(kind, model.as_deref(), &settings).await,
+        style: PromptStyle::Simple,
     };
-    client.translate(request).await
+    let direction = settings.lock().await.direction.clone();
+    
+    run_translation(engine, pending, direction, resolve_request_id(request_id), &requests, &app).await
 }
 
 #[tauri::command]
 async fn detect_language(
     text: String,
-    state: State<'_, Arc<Mutex<OllamaClient>>>,
-) -> Result<DetectLanguageResponse, String> {
-    let client = state.lock().await;
-    let request = DetectLanguageRequest { text };
-    client.detect_language(request).await
+    top_k: Option<usize>,
+    engine: Option<EngineKind>,
+    engines: State<'_, Arc<Engines>>,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<DetectLanguageResponse, NeuralError> {
+    let request = DetectLanguageRequest { text, top_k };
+    engines.get(resolve_engine_kind(engine, &settings).await).detect_language(request).await
+}
+
+/// Every language commands accept, with the tag they are returned as and display names for pickers
+#[tauri::command]
+async fn get_languages() -> Result<Vec<LanguageInfo>, NeuralError> {
+    Ok(Language::ALL.into_iter().map(LanguageInfo::from).collect())
+}
+
+/// Split mixed-language text into labelled spans; runs locally whichever engine is selected
+#[tauri::command]
+async fn detect_language_segments(text: String) -> Result<Vec<LanguageSpan>, NeuralError> {
+    Ok(langdetect::detect_segments(&text))
 }
 
 #[tauri::command]
 async fn check_ollama_health(
-    state: State<'_, Arc<Mutex<OllamaClient>>>,
-) -> Result<bool, String> {
-    let client = state.lock().await;
-    client.check_health().await
+    state: State<'_, Arc<SharedOllamaClient>>,
+) -> Result<HealthReport, NeuralError> {
+    Ok(state.current().check_health().await)
+}
+
+#[tauri::command]
+async fn check_engine_health(
+    engine: Option<EngineKind>,
+    engines: State<'_, Arc<Engines>>,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<HealthReport, NeuralError> {
+    Ok(engines.get(resolve_engine_kind(engine, &settings).await).check_health().await)
+}
+
+#[tauri::command]
+async fn list_engine_models(
+    engine: Option<EngineKind>,
+    engines: State<'_, Arc<Engines>>,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<Vec<String>, NeuralError> {
+    engines.get(resolve_engine_kind(engine, &settings).await).list_models().await
+}
+
+/// Translate while emitting `translation-chunk` events per token batch, `translation-progress` per document chunk
+/// and a final `translation-done` event
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn translate_stream(
+    request_id: String,
+    text: String,
+    from_lang: String,
+    to_lang: String,
+    model: Option<String>,
+    engine: Option<EngineKind>,
+    app: AppHandle,
+    engines: State<'_, Arc<Engines>>,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+    requests: State<'_, Arc<RequestRegistry>>,
+) -> Result<TranslateResponse, NeuralError> {
+    let kind = resolve_engine_kind(engine, &settings).await;
+    let engine = engines.get(kind);
+    let models = candidate_models(kind, model.as_deref(), &settings).await;
+    let direction = settings.lock().await.direction.clone();
+    // Parsed here but checked inside the request, so unsupported languages also end in `translation-done`
+    let (from_lang, to_lang) = (LanguageChoice::parse(&from_lang), LanguageChoice::parse(&to_lang));
+    
+    let mut on_chunk = |delta: &str| {
+        let event = TranslationChunkEvent {
+            request_id: request_id.clone(),
+            delta: delta.to_string(),
+        };
+        if let Err(e) = app.emit("translation-chunk", event) {
+            tracing::error!("Failed to emit translation-chunk event: {}", e);
+        }
+    };
+    
+    let mut on_progress = |progress: ChunkProgress| emit_progress(&app, &request_id, progress);
+    let (engine, on_chunk, on_progress) = (engine.as_ref(), &mut on_chunk, &mut on_progress);
+    let translation = async move {
+        let pending = PendingTranslation { text, from_lang: from_lang?, to_lang: to_lang?, models, style: PromptStyle::Simple };
+        let request = pending.resolve(engine, &direction).await?;
+        translate_chunked(engine, request, CHUNK_TOKEN_BUDGET, Some(on_chunk), on_progress).await
+    };
+    
+    let result = requests.run(&request_id, translation)
+        .await
+        .map(|mut response| {
+            response.request_id = Some(request_id.clone());
+            response
+        });
+    
+    let done_event = match &result {
+        Ok(response) => TranslationDoneEvent {
+            request_id: request_id.clone(),
+            translated_text: Some(response.translated_text.clone()),
+            error: None,
+        },
+        Err(e) => TranslationDoneEvent {
+            request_id: request_id.clone(),
+            translated_text: None,
+            error: Some(e.clone()),
+        },
+    };
+    if let Err(e) = app.emit("translation-done", done_event) {
+        tracing::error!("Failed to emit translation-done event: {}", e);
+    }
+    
+    result
+}
+
+// ===== Model Management Commands =====
+
+#[derive(Debug, Serialize, Clone)]
+struct ModelDownloadProgressEvent {
+    model: String,
+    status: String,
+    digest: Option<String>,
+    completed: Option<u64>,
+    total: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct ModelDownloadDoneEvent {
+    model: String,
+    error: Option<NeuralError>,
+}
+
+fn download_request_id(model_id: &str) -> String {
+    format!("download:{}", model_id)
+}
+
+/// Pull a model via Ollama, emitting `model-download-progress` per layer update,
+/// `model-download-done` when it ends, and `models-updated` with the refreshed catalog
+#[tauri::command]
+async fn download_ollama_model(
+    model_id: String,
+    app: AppHandle,
+    state: State<'_, Arc<SharedOllamaClient>>,
+    requests: State<'_, Arc<RequestRegistry>>,
+) -> Result<ModelCatalog, NeuralError> {
+    let client = state.current();
+    
+    let result = requests.run(&download_request_id(&model_id), client.pull_model(&model_id, |progress| {
+        let event = ModelDownloadProgressEvent {
+            model: model_id.clone(),
+            status: progress.status.clone(),
+            digest: progress.digest.clone(),
+            completed: progress.completed,
+            total: progress.total,
+        };
+        if let Err(e) = app.emit("model-download-progress", event) {
+            tracing::error!("Failed to emit model-download-progress event: {}", e);
+        }
+    })).await;
+    
+    let done_event = ModelDownloadDoneEvent {
+        model: model_id.clone(),
+        error: result.as_ref().err().cloned(),
+    };
+    if let Err(e) = app.emit("model-download-done", done_event) {
+        tracing::error!("Failed to emit model-download-done event: {}", e);
+    }
+    result?;
+    
+    let catalog = client.model_catalog().await?;
+    if let Err(e) = app.emit("models-updated", catalog.clone()) {
+        tracing::error!("Failed to emit models-updated event: {}", e);
+    }
+    
+    Ok(catalog)
+}
+
+#[tauri::command]
+async fn delete_model(
+    model_name: String,
+    app: AppHandle,
+    state: State<'_, Arc<SharedOllamaClient>>,
+) -> Result<ModelCatalog, NeuralError> {
+    let client = state.current();
+    client.delete_model(&model_name).await?;
+    
+    let catalog = client.model_catalog().await?;
+    if let Err(e) = app.emit("models-updated", catalog.clone()) {
+        tracing::error!("Failed to emit models-updated event: {}", e);
+    }
+    
+    Ok(catalog)
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ModelStorage {
+    pub name: String,
+    pub size_bytes: u64,
+    pub parameter_size: Option<String>,
+    pub quantization: Option<String>,
+    pub modified_at: Option<String>,
+    pub last_used: Option<u64>, // Timestamp of the newest history entry produced by this model
+    pub translation_count: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ModelStorageReport {
+    pub total_size_bytes: u64,
+    pub models: Vec<ModelStorage>, // Largest first
+}
+
+/// Per-model disk usage joined with usage statistics from the translation history
+#[tauri::command]
+async fn get_model_storage(
+    history_path: Option<String>,
+    state: State<'_, Arc<SharedOllamaClient>>,
+) -> Result<ModelStorageReport, NeuralError> {
+    let installed = state.current().installed_models().await?;
+    
+    // Missing or unreadable history only means we can't report usage, not that storage is unknown
+    let history = match read_history_file(history_path) {
+        Ok(history_file) => history_file.map(|h| h.translations).unwrap_or_default(),
+        Err(e) => {
+            tracing::warn!("Model storage report without usage data: {}", e);
+            Vec::new()
+        }
+    };
+    
+    Ok(model_storage_report(installed, &history))
+}
+
+/// Join installed models with the Ollama translations each one produced in the history
+fn model_storage_report(installed: Vec<InstalledModel>, history: &[TranslationHistory]) -> ModelStorageReport {
+    let mut models: Vec<ModelStorage> = installed.into_iter()
+        .map(|model| {
+            let usage: Vec<&TranslationHistory> = history.iter()
+                .filter(|entry| entry.engine == "ollama")
+                .filter(|entry| entry.model.as_deref().is_some_and(|name| model_name_matches(&model.name, name)))
+                .collect();
+            
+            ModelStorage {
+                last_used: usage.iter().map(|entry| entry.timestamp).max(),
+                translation_count: usage.len(),
+                name: model.name,
+                size_bytes: model.size,
+                parameter_size: model.parameter_size,
+                quantization: model.quantization,
+                modified_at: model.modified_at,
+            }
+        })
+        .collect();
+    
+    models.sort_by_key(|model| std::cmp::Reverse(model.size_bytes));
+    
+    ModelStorageReport {
+        total_size_bytes: models.iter().map(|model| model.size_bytes).sum(),
+        models,
+    }
+}
+
+#[tauri::command]
+async fn cancel_model_download(
+    model_id: String,
+    requests: State<'_, Arc<RequestRegistry>>,
+) -> Result<bool, NeuralError> {
+    Ok(requests.cancel(&download_request_id(&model_id)))
+}
+
+// ===== Backend Configuration Commands =====
+
+#[tauri::command]
+async fn get_backend_config(
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<BackendConfig, NeuralError> {
+    Ok(settings.lock().await.backend.clone())
+}
+
+#[tauri::command]
+async fn set_backend_config(
+    config: BackendConfig,
+    state: State<'_, Arc<SharedOllamaClient>>,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<BackendConfig, NeuralError> {
+    let config = config.normalized()?;
+    let mut settings = settings.lock().await;
+    
+    // Build the new client first so an invalid config never replaces a working one
+    let new_client = OllamaClient::from_config(&config)?.with_inference(settings.inference.clone());
+    
+    settings.backend = config.clone();
+    settings.save()?;
+    
+    // Generations still running on the old client keep their slots under the shared limit
+    state.replace(new_client.with_slots_of(&state.current()));
+    tracing::info!("🔧 Ollama backend switched to {}", config.base_url);
+    
+    Ok(config)
+}
+
+#[tauri::command]
+async fn get_openai_config(
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<OpenAiConfig, NeuralError> {
+    Ok(settings.lock().await.openai.clone())
+}
+
+#[tauri::command]
+async fn set_openai_config(
+    config: OpenAiConfig,
+    state: State<'_, Arc<SharedOpenAiClient>>,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<OpenAiConfig, NeuralError> {
+    let config = config.normalized()?;
+    let new_client = OpenAiClient::from_config(&config)?;
+    
+    let mut settings = settings.lock().await;
+    settings.openai = config.clone();
+    settings.save()?;
+    
+    state.replace(new_client);
+    tracing::info!("🔧 OpenAI-compatible backend switched to {}", config.base_url);
+    
+    Ok(config)
+}
+
+#[tauri::command]
+async fn get_libretranslate_config(
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<LibreTranslateConfig, NeuralError> {
+    Ok(settings.lock().await.libretranslate.clone())
+}
+
+#[tauri::command]
+async fn set_libretranslate_config(
+    config: LibreTranslateConfig,
+    state: State<'_, Arc<SharedLibreTranslateClient>>,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<LibreTranslateConfig, NeuralError> {
+    let config = config.normalized()?;
+    let new_client = LibreTranslateClient::from_config(&config)?;
+    
+    let mut settings = settings.lock().await;
+    settings.libretranslate = config.clone();
+    settings.save()?;
+    
+    state.replace(new_client);
+    tracing::info!("🔧 LibreTranslate backend switched to {}", config.base_url);
+    
+    Ok(config)
+}
+
+#[tauri::command]
+async fn get_inference_settings(
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<InferenceSettings, NeuralError> {
+    Ok(settings.lock().await.inference.clone())
+}
+
+/// Recommended profile for this machine, for resetting an edited profile
+#[tauri::command]
+async fn detect_inference_profile() -> Result<InferenceProfile, NeuralError> {
+    Ok(InferenceProfile::detect())
+}
+
+/// Save inference settings and rebuild the Ollama client so new requests use them
+fn apply_inference_settings(
+    inference: InferenceSettings,
+    state: &SharedOllamaClient,
+    settings: &mut AppSettings,
+) -> Result<InferenceSettings, NeuralError> {
+    let new_client = OllamaClient::from_config(&settings.backend)?.with_inference(inference.clone());
+    
+    settings.inference = inference.clone();
+    settings.save()?;
+    
+    state.replace(new_client.with_slots_of(&state.current()));
+    Ok(inference)
+}
+
+#[tauri::command]
+async fn set_default_inference_profile(
+    profile: InferenceProfile,
+    state: State<'_, Arc<SharedOllamaClient>>,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<InferenceSettings, NeuralError> {
+    let mut settings = settings.lock().await;
+    let mut inference = settings.inference.clone();
+    inference.default_profile = profile.normalized()?;
+    
+    tracing::info!("🔧 Default inference profile updated");
+    apply_inference_settings(inference, &state, &mut settings)
+}
+
+/// Save a per-model override, or remove it with `profile: null` so the model uses the default again
+#[tauri::command]
+async fn set_model_inference_profile(
+    model: String,
+    profile: Option<InferenceProfile>,
+    state: State<'_, Arc<SharedOllamaClient>>,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<InferenceSettings, NeuralError> {
+    let model = model.trim().to_string();
+    if model.is_empty() {
+        return Err(NeuralError::InvalidInput("Model name is required".to_string()));
+    }
+    
+    let mut settings = settings.lock().await;
+    let mut inference = settings.inference.clone();
+    match profile {
+        Some(profile) => {
+            inference.model_profiles.insert(model.clone(), profile.normalized()?);
+        }
+        None => {
+            inference.model_profiles.remove(&model);
+        }
+    }
+    
+    tracing::info!("🔧 Inference profile updated for {}", model);
+    apply_inference_settings(inference, &state, &mut settings)
+}
+
+#[tauri::command]
+async fn get_default_engine(
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<EngineKind, NeuralError> {
+    Ok(settings.lock().await.default_engine)
+}
+
+#[tauri::command]
+async fn set_default_engine(
+    engine: EngineKind,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<EngineKind, NeuralError> {
+    let mut settings = settings.lock().await;
+    settings.default_engine = engine;
+    settings.save()?;
+    
+    tracing::info!("🔧 Default translation engine set to {:?}", engine);
+    Ok(engine)
 }
 
 // ===== Enhanced Ollama Translation Commands =====
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn translate_with_prompt(
     text: String,
     from_lang: String,
     to_lang: String,
-    state: State<'_, Arc<Mutex<OllamaClient>>>,
-) -> Result<TranslateResponse, String> {
-    let client = state.lock().await;
-    
-    // Create optimized translation prompt with enhanced instructions
-    let translation_prompt = format!(
-        "You are an expert professional translator specializing in {} to {} translation.\n\nInstructions:\n- Translate accurately while preserving context, tone, and cultural nuances\n- Maintain the original formatting and structure\n- For technical terms, use widely accepted translations\n- For proper nouns, keep them as-is unless standard translations exist\n- Return ONLY the translation, no explanations or notes\n\nText to translate:\n{}",
-        from_lang, to_lang, text
-    );
-    
-    let request = TranslateRequest {
-        text: translation_prompt,
-        from_lang: from_lang.clone(),
-        to_lang: to_lang.clone(),
+    model: Option<String>,
+    engine: Option<EngineKind>,
+    request_id: Option<String>,
+    app: AppHandle,
+    engines: State<'_, Arc<Engines>>,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+    requests: State<'_, Arc<RequestRegistry>>,
+) -> Result<TranslateResponse, NeuralError> {
+    // Same translation with enhanced professional-translator instructions
+    let kind = resolve_engine_kind(engine, &settings).await;
+    let engine = engines.get(kind);
+    let pending = PendingTranslation {
+        text,
+        from_lang: LanguageChoice::parse(&from_lang)?,
+        to_lang: LanguageChoice::parse(&to_lang)?,
+        models: candidate_models(kind, model.as_deref(), &settings).await,
+        style: PromptStyle::Professional,
     };
+    let direction = settings.lock().await.direction.clone();
+    
+    run_translation(engine, pending, direction, resolve_request_id(request_id), &requests, &app).await
+}
+
+#[tauri::command]
+async fn cancel_translation(
+    request_id: String,
+    requests: State<'_, Arc<RequestRegistry>>,
+) -> Result<bool, NeuralError> {
+    Ok(requests.cancel(&request_id))
+}
+
+#[tauri::command]
+async fn get_translation_models(
+    state: State<'_, Arc<SharedOllamaClient>>,
+) -> Result<ModelCatalog, NeuralError> {
+    // Installed models merged with our recommended list (in priority order)
+    state.current().model_catalog().await
+}
+
+#[tauri::command]
+async fn get_model_preferences(
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<ModelPreferences, NeuralError> {
+    Ok(settings.lock().await.models.clone())
+}
+
+#[tauri::command]
+async fn set_model_preferences(
+    preferences: ModelPreferences,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<ModelPreferences, NeuralError> {
+    let preferences = preferences.normalized();
+    
+    if preferences.candidates(None).is_empty() {
+        return Err(NeuralError::InvalidInput("Select a preferred model or keep at least one fallback model".to_string()));
+    }
+    
+    let mut settings = settings.lock().await;
+    settings.models = preferences.clone();
+    settings.save()?;
     
-    client.translate_with_prompt(request).await
+    Ok(preferences)
 }
 
 #[tauri::command]
-async fn get_translation_models() -> Result<Vec<String>, String> {
-    // Return recommended models for translation in priority order
-    Ok(vec![
-        "aya:8b".to_string(),                    // Translation-specialized multilingual model
-        "qwen2.5:3b".to_string(),               // Lightweight translation-optimized model
-        "llama3.3:8b-instruct".to_string(),     // High-quality general model with instruction following
-        "llama3.1:8b".to_string(),              // Proven general model
-        "gemma3:3b".to_string(),                // Fast lightweight alternative
-        "phi4-mini".to_string(),                // Ultra-lightweight fallback
-    ])
+async fn get_direction_preferences(
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<DirectionPreferences, NeuralError> {
+    Ok(settings.lock().await.direction.clone())
 }
 
+/// Languages `auto` translations pick their target from, most preferred first
 #[tauri::command]
+async fn set_direction_preferences(
+    preferences: DirectionPreferences,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+) -> Result<DirectionPreferences, NeuralError> {
+    let preferences = preferences.normalized()?;
+    
+    let mut settings = settings.lock().await;
+    settings.direction = preferences.clone();
+    settings.save()?;
+    
+    Ok(preferences)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn improve_text(
     text: String,
     language: String,
-    state: State<'_, Arc<Mutex<OllamaClient>>>,
-) -> Result<TranslateResponse, String> {
-    let client = state.lock().await;
-    
-    // Create specialized text improvement prompt based on language
-    let improvement_prompt = match language.as_str() {
-        "Japanese" => format!(
-            "あなたは日本語の校正・文章改善のプロフェッショナルです。以下の指示に従ってテキストを改善してください：\n\n指示：\n- より自然で読みやすい日本語に改善\n- 文法的な誤りを修正\n- 表現をより洗練させる\n- 読み手にとって分かりやすくする\n- 改善した文章のみを返す（説明は不要）\n\n改善するテキスト：\n{}",
-            text
-        ),
-        "English" => format!(
-            "You are a professional English editor and writing improvement specialist. Please improve the following text according to these instructions:\n\nInstructions:\n- Make the English more natural and fluent\n- Fix any grammatical errors\n- Enhance clarity and readability\n- Improve word choice and style\n- Return only the improved text (no explanations needed)\n\nText to improve:\n{}",
-            text
-        ),
-        "Chinese" => format!(
-            "您是专业的中文文本校对和改进专家。请按照以下指示改进文本：\n\n指示：\n- 使中文更加自然流畅\n- 修正语法错误\n- 提高表达的准确性和可读性\n- 优化用词和语言风格\n- 只返回改进后的文本（无需说明）\n\n需要改进的文本：\n{}",
-            text
-        ),
-        "Korean" => format!(
-            "당신은 한국어 교정 및 문장 개선 전문가입니다. 다음 지시사항에 따라 텍스트를 개선해주세요:\n\n지시사항:\n- 더 자연스럽고 읽기 쉬운 한국어로 개선\n- 문법적 오류 수정\n- 표현을 더 세련되게 만들기\n- 읽는 사람이 이해하기 쉽게 하기\n- 개선된 문장만 반환 (설명 불필요)\n\n개선할 텍스트:\n{}",
-            text
-        ),
-        "Spanish" => format!(
-            "Eres un experto profesional en corrección y mejora de textos en español. Por favor, mejora el siguiente texto según estas instrucciones:\n\nInstrucciones:\n- Hacer el español más natural y fluido\n- Corregir errores gramaticales\n- Mejorar la claridad y legibilidad\n- Perfeccionar la elección de palabras y el estilo\n- Devolver solo el texto mejorado (no se necesitan explicaciones)\n\nTexto a mejorar:\n{}",
-            text
-        ),
-        "French" => format!(
-            "Vous êtes un expert professionnel en correction et amélioration de textes français. Veuillez améliorer le texte suivant selon ces instructions :\n\nInstructions :\n- Rendre le français plus naturel et fluide\n- Corriger les erreurs grammaticales\n- Améliorer la clarté et la lisibilité\n- Perfectionner le choix des mots et le style\n- Retourner uniquement le texte amélioré (aucune explication nécessaire)\n\nTexte à améliorer :\n{}",
-            text
-        ),
-        "German" => format!(
-            "Sie sind ein professioneller Experte für deutsche Textkorrektur und -verbesserung. Bitte verbessern Sie den folgenden Text gemäß diesen Anweisungen:\n\nAnweisungen:\n- Das Deutsche natürlicher und flüssiger gestalten\n- Grammatikfehler korrigieren\n- Klarheit und Lesbarkeit verbessern\n- Wortwahl und Stil verfeinern\n- Nur den verbesserten Text zurückgeben (keine Erklärungen erforderlich)\n\nZu verbessernder Text:\n{}",
-            text
-        ),
-        _ => format!(
-            "You are a professional text editor and improvement specialist. Please improve the following text to make it more natural, clear, and well-written. Fix any grammatical errors and enhance readability. Return only the improved text without explanations.\n\nText to improve:\n{}",
-            text
-        )
-    };
-    
-    let request = TranslateRequest {
-        text: improvement_prompt,
-        from_lang: language.clone(),
-        to_lang: language, // Same language for improvement
+    model: Option<String>,
+    engine: Option<EngineKind>,
+    request_id: Option<String>,
+    app: AppHandle,
+    engines: State<'_, Arc<Engines>>,
+    settings: State<'_, Arc<Mutex<AppSettings>>>,
+    requests: State<'_, Arc<RequestRegistry>>,
+) -> Result<TranslateResponse, NeuralError> {
+    let kind = resolve_engine_kind(engine, &settings).await;
+    let language = LanguageChoice::parse(&language)?;
+    let pending = PendingTranslation {
+        text,
+        from_lang: language,
+        to_lang: language, // Same language for improvement; "auto" polishes in the detected language
+        models: candidate_models(kind, model.as_deref(), &settings).await,
+        style: PromptStyle::Improve,
     };
+    let direction = settings.lock().await.direction.clone();
     
-    client.translate_with_prompt(request).await
+    run_translation(engines.get(kind), pending, direction, resolve_request_id(request_id), &requests, &app).await
 }
 
 // ===== File Processing Commands =====
 
 #[tauri::command]
-async fn read_file_content(file_path: String) -> Result<String, String> {
+async fn read_file_content(file_path: String) -> Result<String, NeuralError> {
     let path = Path::new(&file_path);
     
     if !path.exists() {
-        return Err("File not found".to_string());
+        return Err(NeuralError::FileNotFound { path: file_path });
     }
     
     let extension = path.extension()
@@ -232,10 +807,10 @@ async fn read_file_content(file_path: String) -> Result<String, String> {
     }
 }
 
-async fn read_text_file(file_path: &str) -> Result<String, String> {
+async fn read_text_file(file_path: &str) -> Result<String, NeuralError> {
     // Try to read the file with different encodings
     let bytes = fs::read(file_path)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+        .map_err(|e| NeuralError::Io(format!("Failed to read file: {}", e)))?;
     
     // Detect encoding and decode
     let (cow, _encoding_used, _had_errors) = encoding_rs::UTF_8.decode(&bytes);
@@ -254,15 +829,15 @@ async fn read_text_file(file_path: &str) -> Result<String, String> {
     Ok(cow.into_owned())
 }
 
-async fn read_docx_file(file_path: &str) -> Result<String, String> {
+async fn read_docx_file(file_path: &str) -> Result<String, NeuralError> {
     use docx_rs::*;
     
     // Read file as bytes
     let bytes = fs::read(file_path)
-        .map_err(|e| format!("Failed to read DOCX file: {}", e))?;
+        .map_err(|e| NeuralError::Io(format!("Failed to read DOCX file: {}", e)))?;
     
     let docx = read_docx(&bytes)
-        .map_err(|e| format!("Failed to parse DOCX file: {}", e))?;
+        .map_err(|e| NeuralError::Parse(format!("Failed to parse DOCX file: {}", e)))?;
     
     // Extract text from all paragraphs - updated for current docx-rs API
     let mut text_content = String::new();
@@ -285,11 +860,11 @@ async fn read_docx_file(file_path: &str) -> Result<String, String> {
     Ok(text_content.trim().to_string())
 }
 
-async fn read_pdf_file(file_path: &str) -> Result<String, String> {
+async fn read_pdf_file(file_path: &str) -> Result<String, NeuralError> {
     use lopdf::Document;
     
     let doc = Document::load(file_path)
-        .map_err(|e| format!("Failed to load PDF file: {}", e))?;
+        .map_err(|e| NeuralError::Parse(format!("Failed to load PDF file: {}", e)))?;
     
     let mut text_content = String::new();
     
@@ -308,14 +883,14 @@ async fn read_pdf_file(file_path: &str) -> Result<String, String> {
     }
     
     if text_content.trim().is_empty() {
-        return Err("Could not extract text from PDF file".to_string());
+        return Err(NeuralError::NoText);
     }
     
     Ok(text_content.trim().to_string())
 }
 
 #[tauri::command]
-async fn validate_file_type(file_path: String) -> Result<String, String> {
+async fn validate_file_type(file_path: String) -> Result<String, NeuralError> {
     let path = Path::new(&file_path);
     
     let extension = path.extension()
@@ -327,17 +902,17 @@ async fn validate_file_type(file_path: String) -> Result<String, String> {
         "txt" => Ok("text".to_string()),
         "docx" => Ok("docx".to_string()),
         "pdf" => Ok("pdf".to_string()),
-        _ => Err("Unsupported file type".to_string()),
+        _ => Err(NeuralError::UnsupportedFile { extension }),
     }
 }
 
 #[tauri::command]
-async fn process_file_content(file_data: String, file_name: String) -> Result<String, String> {
+async fn process_file_content(file_data: String, file_name: String) -> Result<String, NeuralError> {
     use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
     
     // Decode base64 file data
     let file_bytes = BASE64.decode(&file_data)
-        .map_err(|e| format!("Failed to decode file data: {}", e))?;
+        .map_err(|e| NeuralError::InvalidInput(format!("Failed to decode file data: {}", e)))?;
     
     // Get file extension
     let extension = file_name.split('.').last()
@@ -353,7 +928,7 @@ async fn process_file_content(file_data: String, file_name: String) -> Result<St
     
     // Write decoded data to temporary file
     fs::write(&temp_file_path, file_bytes)
-        .map_err(|e| format!("Failed to write temporary file: {}", e))?;
+        .map_err(|e| NeuralError::Io(format!("Failed to write temporary file: {}", e)))?;
     
     // Process the file based on extension
     let result = match extension.as_str() {
@@ -367,7 +942,7 @@ async fn process_file_content(file_data: String, file_name: String) -> Result<St
             read_pdf_file(temp_file_path.to_str().unwrap()).await
         }
         _ => {
-            Err(format!("Unsupported file type: {}", extension))
+            Err(NeuralError::UnsupportedFile { extension })
         }
     };
     
@@ -380,6 +955,7 @@ async fn process_file_content(file_data: String, file_name: String) -> Result<St
 // ===== Translation History Commands =====
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn save_translation_history(
     source_text: String,
     translated_text: String,
@@ -387,8 +963,9 @@ async fn save_translation_history(
     to_language: String,
     engine: String,
     latency_ms: Option<u32>,
+    model: Option<String>,
     history_path: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, NeuralError> {
     let timestamp = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
@@ -399,10 +976,11 @@ async fn save_translation_history(
         timestamp,
         source_text,
         translated_text,
-        from_language,
-        to_language,
+        from_language: Language::parse(&from_language)?.tag().to_string(),
+        to_language: Language::parse(&to_language)?.tag().to_string(),
         engine,
         latency_ms,
+        model,
     };
     
     let default_path = get_default_history_directory();
@@ -410,7 +988,7 @@ async fn save_translation_history(
     
     // Create history directory if it doesn't exist
     if let Err(e) = fs::create_dir_all(&history_dir) {
-        return Err(format!("Failed to create history directory: {}", e));
+        return Err(NeuralError::Io(format!("Failed to create history directory: {}", e)));
     }
     
     let history_file_path = Path::new(&history_dir).join("translation_history.json");
@@ -418,9 +996,9 @@ async fn save_translation_history(
     // Load existing history or create new
     let mut history_file = if history_file_path.exists() {
         let content = fs::read_to_string(&history_file_path)
-            .map_err(|e| format!("Failed to read history file: {}", e))?;
+            .map_err(|e| NeuralError::Io(format!("Failed to read history file: {}", e)))?;
         serde_json::from_str::<HistoryFile>(&content)
-            .map_err(|e| format!("Failed to parse history file: {}", e))?
+            .map_err(|e| NeuralError::HistoryCorrupt(e.to_string()))?
     } else {
         HistoryFile {
             version: "1.0".to_string(),
@@ -441,10 +1019,10 @@ async fn save_translation_history(
     
     // Save updated history
     let json_content = serde_json::to_string_pretty(&history_file)
-        .map_err(|e| format!("Failed to serialize history: {}", e))?;
+        .map_err(|e| NeuralError::Parse(format!("Failed to serialize history: {}", e)))?;
     
     fs::write(&history_file_path, json_content)
-        .map_err(|e| format!("Failed to write history file: {}", e))?;
+        .map_err(|e| NeuralError::Io(format!("Failed to write history file: {}", e)))?;
     
     Ok(history_entry.id)
 }
@@ -453,20 +1031,11 @@ async fn save_translation_history(
 async fn load_translation_history(
     history_path: Option<String>,
     limit: Option<usize>,
-) -> Result<Vec<TranslationHistory>, String> {
-    let default_path = get_default_history_directory();
-    let history_dir = history_path.unwrap_or(default_path);
-    let history_file_path = Path::new(&history_dir).join("translation_history.json");
-    
-    if !history_file_path.exists() {
-        return Ok(Vec::new()); // Return empty vec if no history file exists
-    }
-    
-    let content = fs::read_to_string(&history_file_path)
-        .map_err(|e| format!("Failed to read history file: {}", e))?;
-    
-    let history_file: HistoryFile = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse history file: {}", e))?;
+) -> Result<Vec<TranslationHistory>, NeuralError> {
+    let history_file = match read_history_file(history_path)? {
+        Some(history_file) => history_file,
+        None => return Ok(Vec::new()), // Return empty vec if no history file exists
+    };
     
     let mut translations = history_file.translations;
     
@@ -482,45 +1051,56 @@ async fn load_translation_history(
 }
 
 #[tauri::command]
-async fn clear_translation_history(history_path: Option<String>) -> Result<(), String> {
+async fn clear_translation_history(history_path: Option<String>) -> Result<(), NeuralError> {
     let default_path = get_default_history_directory();
     let history_dir = history_path.unwrap_or(default_path);
     let history_file_path = Path::new(&history_dir).join("translation_history.json");
     
     if history_file_path.exists() {
         fs::remove_file(&history_file_path)
-            .map_err(|e| format!("Failed to delete history file: {}", e))?;
+            .map_err(|e| NeuralError::Io(format!("Failed to delete history file: {}", e)))?;
     }
     
     Ok(())
 }
 
 #[tauri::command]
-async fn get_history_stats(history_path: Option<String>) -> Result<serde_json::Value, String> {
+async fn get_history_stats(history_path: Option<String>) -> Result<serde_json::Value, NeuralError> {
+    let history_file = match read_history_file(history_path)? {
+        Some(history_file) => history_file,
+        None => {
+            return Ok(serde_json::json!({
+                "total_translations": 0,
+                "created_at": null,
+                "updated_at": null
+            }));
+        }
+    };
+    
+    Ok(serde_json::json!({
+        "total_translations": history_file.translations.len(),
+        "created_at": history_file.created_at,
+        "updated_at": history_file.updated_at,
+        "version": history_file.version
+    }))
+}
+
+/// Read the history file from `history_path` (or the default directory); `None` if it doesn't exist yet
+fn read_history_file(history_path: Option<String>) -> Result<Option<HistoryFile>, NeuralError> {
     let default_path = get_default_history_directory();
     let history_dir = history_path.unwrap_or(default_path);
     let history_file_path = Path::new(&history_dir).join("translation_history.json");
     
     if !history_file_path.exists() {
-        return Ok(serde_json::json!({
-            "total_translations": 0,
-            "created_at": null,
-            "updated_at": null
-        }));
+        return Ok(None);
     }
     
     let content = fs::read_to_string(&history_file_path)
-        .map_err(|e| format!("Failed to read history file: {}", e))?;
+        .map_err(|e| NeuralError::Io(format!("Failed to read history file: {}", e)))?;
     
-    let history_file: HistoryFile = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse history file: {}", e))?;
-    
-    Ok(serde_json::json!({
-        "total_translations": history_file.translations.len(),
-        "created_at": history_file.created_at,
-        "updated_at": history_file.updated_at,
-        "version": history_file.version
-    }))
+    serde_json::from_str::<HistoryFile>(&content)
+        .map(Some)
+        .map_err(|e| NeuralError::HistoryCorrupt(e.to_string()))
 }
 
 fn get_default_history_directory() -> String {
@@ -536,7 +1116,7 @@ fn get_default_history_directory() -> String {
 // ===== System Metrics Commands =====
 
 #[tauri::command]
-async fn get_system_metrics() -> Result<serde_json::Value, String> {
+async fn get_system_metrics() -> Result<serde_json::Value, NeuralError> {
     let mut sys = System::new_all();
     sys.refresh_all();
     
@@ -622,22 +1202,48 @@ async fn get_system_metrics() -> Result<serde_json::Value, String> {
 }
 
 #[tauri::command]
-async fn get_model_metrics(model_name: String) -> Result<serde_json::Value, String> {
-    // Get system metrics before/after model operations
+async fn get_model_metrics(
+    model_name: String,
+    state: State<'_, Arc<SharedOllamaClient>>,
+) -> Result<serde_json::Value, NeuralError> {
+    collect_model_metrics(&state.current(), &model_name).await
+}
+
+/// Load a model into memory ahead of time; `keep_alive` is seconds ("-1" = forever) or a duration like "30m"
+#[tauri::command]
+async fn preload_model(
+    model_name: String,
+    keep_alive: Option<String>,
+    state: State<'_, Arc<SharedOllamaClient>>,
+) -> Result<serde_json::Value, NeuralError> {
+    let client = state.current();
+    client.load_model(&model_name, keep_alive.as_deref()).await?;
+    collect_model_metrics(&client, &model_name).await
+}
+
+/// Evict a model from memory immediately (`keep_alive: 0`)
+#[tauri::command]
+async fn unload_model(
+    model_name: String,
+    state: State<'_, Arc<SharedOllamaClient>>,
+) -> Result<serde_json::Value, NeuralError> {
+    let client = state.current();
+    client.unload_model(&model_name).await?;
+    collect_model_metrics(&client, &model_name).await
+}
+
+async fn collect_model_metrics(client: &OllamaClient, model_name: &str) -> Result<serde_json::Value, NeuralError> {
+    // Ask Ollama which models are actually resident instead of guessing from process memory
+    let running_models = client.running_models().await?;
+    let resident = running_models.iter()
+        .find(|model| model_name_matches(&model.name, model_name));
+    
+    // Get current Ollama memory usage
     let mut sys = System::new_all();
     sys.refresh_all();
     
-    // Estimate model memory usage based on model name
-    let estimated_memory_gb = match model_name.as_str() {
-        "aya:8b" => 4.8,
-        "qwen2.5:3b" => 1.9,
-        "llama3.1:8b" => 4.9,
-        _ => 0.0,
-    };
-    
-    // Get current Ollama memory usage
     let mut ollama_memory_mb = 0u64;
-    for (_pid, process) in sys.processes() {
+    for process in sys.processes().values() {
         if process.name().to_string_lossy().to_lowercase().contains("ollama") {
             ollama_memory_mb = process.memory() / 1024; // Convert to MB
             break;
@@ -646,31 +1252,35 @@ async fn get_model_metrics(model_name: String) -> Result<serde_json::Value, Stri
     
     Ok(serde_json::json!({
         "model": model_name,
-        "estimated_size_gb": estimated_memory_gb,
+        "loaded": resident.is_some(),
+        "size_bytes": resident.map(|model| model.size),
+        "vram_bytes": resident.map(|model| model.size_vram),
+        "ram_bytes": resident.map(|model| model.size_ram),
+        "expires_at": resident.and_then(|model| model.expires_at.clone()),
         "current_memory_mb": ollama_memory_mb,
-        "loaded": ollama_memory_mb > 100, // If Ollama is using >100MB, likely a model is loaded
+        "running_models": running_models,
     }))
 }
 
 #[tauri::command]
-async fn get_clipboard_text(app: AppHandle) -> Result<String, String> {
+async fn get_clipboard_text(app: AppHandle) -> Result<String, NeuralError> {
     match app.clipboard().read_text() {
         Ok(text) => Ok(text),
-        Err(e) => Err(format!("Failed to read clipboard: {}", e)),
+        Err(e) => Err(NeuralError::Io(format!("Failed to read clipboard: {}", e))),
     }
 }
 
 #[tauri::command]
-async fn set_clipboard_text(app: AppHandle, text: String) -> Result<(), String> {
+async fn set_clipboard_text(app: AppHandle, text: String) -> Result<(), NeuralError> {
     app.clipboard()
         .write_text(text)
-        .map_err(|e| format!("Failed to write to clipboard: {}", e))
+        .map_err(|e| NeuralError::Io(format!("Failed to write to clipboard: {}", e)))
 }
 
 #[tauri::command]
-fn show_window(window: tauri::Window) -> Result<(), String> {
-    window.show().map_err(|e| e.to_string())?;
-    window.set_focus().map_err(|e| e.to_string())?;
+fn show_window(window: tauri::Window) -> Result<(), NeuralError> {
+    window.show().map_err(|e| NeuralError::Io(e.to_string()))?;
+    window.set_focus().map_err(|e| NeuralError::Io(e.to_string()))?;
     Ok(())
 }
 
@@ -765,21 +1375,86 @@ pub fn run() {
     
     tracing::info!("🚀 Starting Neural Translator...");
     
-    let ollama_client = Arc::new(Mutex::new(OllamaClient::new()));
+    let settings = AppSettings::load();
+    let ollama_client = match OllamaClient::from_config(&settings.backend) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Invalid saved backend configuration, using defaults: {}", e);
+            OllamaClient::new()
+        }
+    }.with_inference(settings.inference.clone());
+    let ollama_client = Arc::new(SharedOllamaClient::new(ollama_client));
+    let openai_client = match OpenAiClient::from_config(&settings.openai) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Invalid saved OpenAI-compatible configuration, using defaults: {}", e);
+            OpenAiClient::from_config(&OpenAiConfig::default())
+                .expect("default OpenAI-compatible configuration is always valid")
+        }
+    };
+    let openai_client = Arc::new(SharedOpenAiClient::new(openai_client));
+    let libretranslate_client = match LibreTranslateClient::from_config(&settings.libretranslate) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Invalid saved LibreTranslate configuration, using defaults: {}", e);
+            LibreTranslateClient::from_config(&LibreTranslateConfig::default())
+                .expect("default LibreTranslate configuration is always valid")
+        }
+    };
+    let libretranslate_client = Arc::new(SharedLibreTranslateClient::new(libretranslate_client));
+    let engines = Arc::new(Engines::new(
+        Arc::clone(&ollama_client),
+        Arc::clone(&openai_client),
+        Arc::clone(&libretranslate_client),
+    ));
+    let settings = Arc::new(Mutex::new(settings));
+    let requests = Arc::new(RequestRegistry::new());
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .manage(ollama_client)
+        .manage(openai_client)
+        .manage(libretranslate_client)
+        .manage(engines)
+        .manage(settings)
+        .manage(requests)
         .invoke_handler(tauri::generate_handler![
             greet,
             translate,
             detect_language,
+            detect_language_segments,
+            get_languages,
             check_ollama_health,
+            check_engine_health,
+            list_engine_models,
+            translate_stream,
+            // Backend configuration commands
+            get_backend_config,
+            set_backend_config,
+            get_openai_config,
+            set_openai_config,
+            get_libretranslate_config,
+            set_libretranslate_config,
+            get_default_engine,
+            set_default_engine,
+            get_inference_settings,
+            detect_inference_profile,
+            set_default_inference_profile,
+            set_model_inference_profile,
             // Enhanced Ollama translation commands
             translate_with_prompt,
+            cancel_translation,
             get_translation_models,
+            get_model_preferences,
+            set_model_preferences,
+            get_direction_preferences,
+            set_direction_preferences,
+            download_ollama_model,
+            cancel_model_download,
+            delete_model,
+            get_model_storage,
             improve_text,
             // File processing commands
             read_file_content,
@@ -793,6 +1468,8 @@ pub fn run() {
             // System metrics commands
             get_system_metrics,
             get_model_metrics,
+            preload_model,
+            unload_model,
             // Utility commands
             get_clipboard_text,
             set_clipboard_text,
@@ -1145,4 +1822,97 @@ mod tests {
             assert!(final_result, "Properly timed tap after rapid succession should work");
         }
     }
+
+    mod model_download_tests {
+        use super::*;
+        use crate::stub_server::{StubResponse, StubServer};
+
+        #[tokio::test]
+        async fn test_cancel_model_download_aborts_the_pull() {
+            // A slow pull: the stub sends one progress line per chunk
+            let lines: Vec<String> = (1..=200)
+                .map(|completed| serde_json::json!({ "status": "pulling", "total": 200, "completed": completed }).to_string() + "\n")
+                .collect();
+            let server = StubServer::start(move |_| StubResponse::streamed("application/x-ndjson", lines.clone())).await;
+            let client = OllamaClient::from_config(&BackendConfig {
+                base_url: server.base_url.clone(),
+                ..BackendConfig::default()
+            }).unwrap();
+            let requests = Arc::new(RequestRegistry::new());
+
+            let canceller = {
+                let requests = Arc::clone(&requests);
+                tokio::spawn(async move {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    requests.cancel(&download_request_id("aya:8b"))
+                })
+            };
+
+            let mut updates = 0;
+            let result = requests
+                .run(&download_request_id("aya:8b"), client.pull_model("aya:8b", |_| updates += 1))
+                .await;
+
+            assert!(canceller.await.unwrap(), "The download should still have been running");
+            assert_eq!(result.unwrap_err().code(), "cancelled");
+            assert!(updates > 0 && updates < 200, "Pull should stop part-way, got {} updates", updates);
+            assert!(!requests.cancel(&download_request_id("aya:8b")), "A cancelled download is no longer tracked");
+        }
+    }
+
+    mod model_storage_tests {
+        use super::*;
+
+        fn installed(name: &str, size: u64) -> InstalledModel {
+            InstalledModel {
+                name: name.to_string(),
+                size,
+                family: None,
+                parameter_size: None,
+                quantization: None,
+                modified_at: None,
+            }
+        }
+
+        fn history_entry(timestamp: u64, engine: &str, model: Option<&str>) -> TranslationHistory {
+            TranslationHistory {
+                id: timestamp.to_string(),
+                timestamp,
+                source_text: "Hello".to_string(),
+                translated_text: "こんにちは".to_string(),
+                from_language: "English".to_string(),
+                to_language: "Japanese".to_string(),
+                engine: engine.to_string(),
+                latency_ms: None,
+                model: model.map(str::to_string),
+            }
+        }
+
+        #[test]
+        fn test_storage_report_counts_ollama_usage_per_model() {
+            let history = vec![
+                history_entry(100, "ollama", Some("aya:8b")),
+                history_entry(300, "ollama", Some("aya:8b")),
+                history_entry(200, "ollama", Some("phi4-mini")),
+                history_entry(400, "ml", Some("aya:8b")),
+                history_entry(500, "ollama", None),
+                history_entry(600, "ollama", Some("aya:35b")),
+            ];
+            let report = model_storage_report(
+                vec![installed("phi4-mini:latest", 2_000), installed("aya:8b", 5_000), installed("qwen2.5:3b", 1_000)],
+                &history,
+            );
+
+            let names: Vec<&str> = report.models.iter().map(|model| model.name.as_str()).collect();
+            assert_eq!(names, vec!["aya:8b", "phi4-mini:latest", "qwen2.5:3b"], "Largest model first");
+            assert_eq!(report.total_size_bytes, 8_000);
+
+            let aya = &report.models[0];
+            assert_eq!((aya.translation_count, aya.last_used), (2, Some(300)), "Other engines and tags don't count");
+            let phi = &report.models[1];
+            assert_eq!((phi.translation_count, phi.last_used), (1, Some(200)), "An untagged name matches :latest");
+            let qwen = &report.models[2];
+            assert_eq!((qwen.translation_count, qwen.last_used), (0, None));
+        }
+    }
 }
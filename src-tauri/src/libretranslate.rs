@@ -0,0 +1,314 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+use crate::config::LibreTranslateConfig;
+use crate::error::NeuralError;
+use crate::engine::{DetectLanguageRequest, DetectLanguageResponse, HealthReport, SharedEngine, TranslateRequest, TranslateResponse, TranslationEngine};
+use crate::langdetect::LanguageCandidate;
+use crate::language::Language;
+use crate::prompts::PromptStyle;
+
+#[derive(Debug, Deserialize)]
+struct TranslateApiResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DetectedLanguage {
+    language: String,
+    #[serde(default)]
+    confidence: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SupportedLanguage {
+    code: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+const SERVICE: &str = "LibreTranslate";
+
+/// Turn a non-success response into an `Api` error carrying the server's own message
+async fn api_error(response: reqwest::Response) -> NeuralError {
+    let status = response.status().as_u16();
+    let body = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+    let message = serde_json::from_str::<ErrorResponse>(&body)
+        .map(|response| response.error)
+        .unwrap_or_else(|_| body.trim().to_string());
+
+    NeuralError::Api { service: SERVICE.to_string(), status, message }
+}
+
+/// LibreTranslate's code for a language; it names the two Chinese scripts "zh" and "zt"
+fn language_code(language: Language) -> &'static str {
+    match language {
+        Language::SimplifiedChinese => "zh",
+        Language::TraditionalChinese => "zt",
+        language => language.tag(),
+    }
+}
+
+/// Language for a code LibreTranslate reports, or None for languages we don't support
+fn code_language(code: &str) -> Option<Language> {
+    match code {
+        "zt" => Some(Language::TraditionalChinese),
+        code => Language::parse(code).ok(),
+    }
+}
+
+/// Client for a self-hosted LibreTranslate-compatible machine translation server
+pub struct LibreTranslateClient {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+/// Managed Tauri state holding the active LibreTranslate client
+pub type SharedLibreTranslateClient = SharedEngine<LibreTranslateClient>;
+
+impl LibreTranslateClient {
+    pub fn from_config(config: &LibreTranslateConfig) -> Result<Self, NeuralError> {
+        let config = config.normalized()?;
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| NeuralError::InvalidInput(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            base_url: config.base_url,
+            api_key: config.api_key,
+        })
+    }
+
+    /// POST a JSON body, adding the API key when one is configured
+    async fn post(&self, endpoint: &str, mut body: serde_json::Value) -> Result<reqwest::Response, NeuralError> {
+        if let Some(api_key) = &self.api_key {
+            body["api_key"] = json!(api_key);
+        }
+
+        let response = self.client
+            .post(format!("{}{}", self.base_url, endpoint))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| NeuralError::from_request(SERVICE, &self.base_url, &format!("call {}", endpoint), e))?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+
+        Ok(response)
+    }
+
+    /// Language codes the server can translate between, from `/languages`
+    async fn supported_languages(&self) -> Result<Vec<String>, NeuralError> {
+        let response = self.client
+            .get(format!("{}/languages", self.base_url))
+            .send()
+            .await
+            .map_err(|e| NeuralError::from_request(SERVICE, &self.base_url, "query supported languages", e))?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+
+        let languages = response.json::<Vec<SupportedLanguage>>().await
+            .map_err(|e| NeuralError::Parse(format!("Failed to parse language list: {}", e)))?;
+
+        Ok(languages.into_iter().map(|language| language.code).collect())
+    }
+}
+
+#[async_trait]
+impl TranslationEngine for LibreTranslateClient {
+    fn name(&self) -> &'static str {
+        "libretranslate"
+    }
+
+    async fn translate(&self, request: TranslateRequest) -> Result<TranslateResponse, NeuralError> {
+        if request.style == PromptStyle::Improve {
+            return Err(NeuralError::Unsupported("LibreTranslate cannot improve text; choose an LLM engine instead".to_string()));
+        }
+
+        let source = language_code(request.from_lang);
+        let target = language_code(request.to_lang);
+        tracing::info!("Starting LibreTranslate translation: {} -> {}", source, target);
+
+        let translation = self
+            .post("/translate", json!({
+                "q": request.text,
+                "source": source,
+                "target": target,
+                "format": "text"
+            }))
+            .await?
+            .json::<TranslateApiResponse>()
+            .await
+            .map_err(|e| NeuralError::Parse(format!("Failed to parse LibreTranslate response: {}", e)))?;
+
+        Ok(TranslateResponse {
+            translated_text: translation.translated_text.trim().to_string(),
+            model: String::new(), // Dedicated MT servers have no selectable model
+            engine: self.name().to_string(),
+            request_id: None,
+            direction: None,
+        })
+    }
+
+    async fn detect_language(&self, request: DetectLanguageRequest) -> Result<DetectLanguageResponse, NeuralError> {
+        let mut detections = self
+            .post("/detect", json!({ "q": request.text }))
+            .await?
+            .json::<Vec<DetectedLanguage>>()
+            .await
+            .map_err(|e| NeuralError::Parse(format!("Failed to parse LibreTranslate detection: {}", e)))?;
+
+        detections.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+
+        // LibreTranslate reports confidence as a percentage
+        let candidates: Vec<LanguageCandidate> = detections
+            .into_iter()
+            .filter_map(|detection| {
+                let language = code_language(&detection.language)?;
+                Some(LanguageCandidate { language, confidence: detection.confidence / 100.0 })
+            })
+            .take(request.top_k())
+            .collect();
+
+        if candidates.is_empty() {
+            return Err(NeuralError::Unsupported("LibreTranslate could not detect a supported language".to_string()));
+        }
+        Ok(DetectLanguageResponse::from_candidates(candidates))
+    }
+
+    async fn check_health(&self) -> HealthReport {
+        let languages = match self.supported_languages().await {
+            Ok(languages) => languages,
+            Err(e) => {
+                tracing::warn!("LibreTranslate health check failed: {}", e);
+                return HealthReport::unreachable(self.name(), e);
+            }
+        };
+
+        let error = if languages.is_empty() {
+            tracing::warn!("⚠ LibreTranslate at {} has no languages installed", self.base_url);
+            Some(NeuralError::Unsupported("LibreTranslate has no languages installed".to_string()))
+        } else {
+            tracing::info!("✓ LibreTranslate is healthy ({} languages)", languages.len());
+            None
+        };
+
+        HealthReport {
+            engine: self.name().to_string(),
+            healthy: error.is_none(),
+            reachable: true,
+            error,
+            ..HealthReport::default()
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, NeuralError> {
+        Ok(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub_server::{StubResponse, StubServer};
+
+    fn client_for(server: &StubServer, api_key: Option<&str>) -> LibreTranslateClient {
+        LibreTranslateClient::from_config(&LibreTranslateConfig {
+            base_url: server.base_url.clone(),
+            api_key: api_key.map(str::to_string),
+            timeout_secs: 5,
+        }).unwrap()
+    }
+
+    fn request(style: PromptStyle) -> TranslateRequest {
+        TranslateRequest {
+            text: "Save changes".to_string(),
+            from_lang: Language::English,
+            to_lang: Language::Japanese,
+            models: Vec::new(),
+            style,
+            context: None,
+        }
+    }
+
+    #[test]
+    fn test_language_code() {
+        assert_eq!(language_code(Language::Japanese), "ja");
+        assert_eq!(language_code(Language::SimplifiedChinese), "zh");
+        assert_eq!(language_code(Language::TraditionalChinese), "zt");
+        assert_eq!(code_language("zt"), Some(Language::TraditionalChinese));
+        assert_eq!(code_language("pt-BR"), Some(Language::Portuguese));
+        assert_eq!(code_language("eo"), None);
+    }
+
+    #[tokio::test]
+    async fn test_translate_maps_languages_and_sends_api_key() {
+        let server = StubServer::start(|_| StubResponse::json(200, json!({ "translatedText": "変更を保存" }))).await;
+        let client = client_for(&server, Some("lt-key"));
+
+        let response = client.translate(request(PromptStyle::Simple)).await.unwrap();
+        assert_eq!(response.translated_text, "変更を保存");
+        assert_eq!(response.engine, "libretranslate");
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/translate");
+        let body = requests[0].json();
+        assert_eq!(body["q"], "Save changes");
+        assert_eq!(body["source"], "en");
+        assert_eq!(body["target"], "ja");
+        assert_eq!(body["api_key"], "lt-key");
+    }
+
+    #[tokio::test]
+    async fn test_improve_is_rejected_without_a_request() {
+        let server = StubServer::start(|_| StubResponse::json(200, json!({ "translatedText": "" }))).await;
+        let client = client_for(&server, None);
+
+        assert_eq!(client.translate(request(PromptStyle::Improve)).await.unwrap_err().code(), "unsupported");
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_detect_picks_most_confident_language() {
+        let server = StubServer::start(|_| StubResponse::json(200, json!([
+            { "language": "es", "confidence": 12.0 },
+            { "language": "eo", "confidence": 30.0 },
+            { "language": "fr", "confidence": 87.5 }
+        ]))).await;
+        let client = client_for(&server, None);
+
+        let detected = client
+            .detect_language(DetectLanguageRequest { text: "Bonjour tout le monde".to_string(), top_k: None })
+            .await
+            .unwrap();
+        assert_eq!(detected.language, Language::French);
+        assert_eq!(detected.confidence, 0.875);
+        assert_eq!(detected.candidates.len(), 2); // Esperanto isn't a language we translate
+        assert!(server.requests()[0].json().get("api_key").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_translate_surfaces_api_error() {
+        let server = StubServer::start(|_| StubResponse::json(400, json!({ "error": "ja is not supported" }))).await;
+        let client = client_for(&server, None);
+
+        let error = client.translate(request(PromptStyle::Simple)).await.unwrap_err();
+        assert!(matches!(error, NeuralError::Api { status: 400, .. }), "Unexpected error: {:?}", error);
+        assert!(error.to_string().contains("ja is not supported"), "Unexpected error: {}", error);
+    }
+}
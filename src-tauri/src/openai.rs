@@ -0,0 +1,473 @@
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+use crate::config::OpenAiConfig;
+use crate::error::NeuralError;
+use crate::engine::{detect_language_locally, ChunkSink, DetectLanguageRequest, DetectLanguageResponse, HealthReport, SharedEngine, TranslateRequest, TranslateResponse, TranslationEngine};
+use crate::prompts::{build_messages, ChatMessage};
+use crate::streaming::{LineStream, TrimmedDeltas};
+
+// ===== Chat Completions Wire Format =====
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    #[serde(default)]
+    model: String,
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ResponseMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionChunk {
+    #[serde(default)]
+    model: String,
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    #[serde(default)]
+    delta: ChunkDelta,
+    finish_reason: Option<String>, // Set on the last chunk of a choice
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChunkDelta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelsResponse {
+    #[serde(default)]
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelEntry {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: ErrorDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorDetail {
+    message: String,
+}
+
+const SERVICE: &str = "OpenAI-compatible";
+
+/// Pull the message out of an OpenAI-style `{"error": {"message": ...}}` body, or return the body as-is
+fn api_error_message(body: &str) -> String {
+    serde_json::from_str::<ErrorResponse>(body)
+        .map(|response| response.error.message)
+        .unwrap_or_else(|_| body.trim().to_string())
+}
+
+/// Turn a non-success response into an `Api` error carrying the server's own message
+async fn api_error(response: reqwest::Response) -> NeuralError {
+    let status = response.status().as_u16();
+    let body = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+    NeuralError::Api { service: SERVICE.to_string(), status, message: api_error_message(&body) }
+}
+
+// ===== Server-Sent Events =====
+
+#[derive(Debug, PartialEq)]
+enum SseLine<'a> {
+    Data(&'a str),
+    Done,
+    Ignored, // Blank separators, comments and non-data fields
+}
+
+fn parse_sse_line(line: &str) -> SseLine<'_> {
+    let Some(data) = line.strip_prefix("data:") else {
+        return SseLine::Ignored;
+    };
+    let data = data.strip_prefix(' ').unwrap_or(data);
+
+    if data.trim() == "[DONE]" {
+        SseLine::Done
+    } else {
+        SseLine::Data(data)
+    }
+}
+
+/// Client for servers speaking the OpenAI chat completions protocol (llama-server, LM Studio, vLLM)
+pub struct OpenAiClient {
+    client: Client,
+    streaming_client: Client, // Times out on silence instead of overall length, so long streams aren't cut off
+    base_url: String,
+    model: String,
+}
+
+/// Managed Tauri state holding the active OpenAI-compatible client
+pub type SharedOpenAiClient = SharedEngine<OpenAiClient>;
+
+impl OpenAiClient {
+    pub fn from_config(config: &OpenAiConfig) -> Result<Self, NeuralError> {
+        let config = config.normalized()?;
+
+        let mut headers = HeaderMap::new();
+        if let Some(api_key) = &config.api_key {
+            let value = HeaderValue::from_str(&format!("Bearer {}", api_key))
+                .map_err(|e| NeuralError::InvalidInput(format!("Invalid API key: {}", e)))?;
+            headers.insert(AUTHORIZATION, value);
+        }
+
+        let timeout = Duration::from_secs(config.timeout_secs);
+        let build = |builder: reqwest::ClientBuilder| {
+            builder
+                .default_headers(headers.clone())
+                .build()
+                .map_err(|e| NeuralError::InvalidInput(format!("Failed to build HTTP client: {}", e)))
+        };
+        let client = build(Client::builder().timeout(timeout))?;
+        let streaming_client = build(Client::builder().read_timeout(timeout))?;
+
+        Ok(Self {
+            client,
+            streaming_client,
+            base_url: config.base_url,
+            model: config.model,
+        })
+    }
+
+    /// An explicitly requested model wins over the configured one
+    fn resolve_model(&self, request: &TranslateRequest) -> String {
+        request.models
+            .first()
+            .cloned()
+            .unwrap_or_else(|| self.model.clone())
+    }
+
+    fn chat_body(&self, model: &str, messages: &[ChatMessage], stream: bool) -> serde_json::Value {
+        let mut body = json!({
+            "messages": messages,
+            "stream": stream,
+            "temperature": 0.3,
+            "top_p": 0.9,
+            "max_tokens": 1024
+        });
+        if !model.is_empty() {
+            body["model"] = json!(model);
+        }
+        body
+    }
+
+    /// Streamed requests go through `streaming_client`
+    async fn post_chat(&self, body: &serde_json::Value) -> Result<reqwest::Response, NeuralError> {
+        let client = if body["stream"] == json!(true) { &self.streaming_client } else { &self.client };
+        let response = client
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| NeuralError::from_request(SERVICE, &self.base_url, "request chat completion", e))?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+
+        Ok(response)
+    }
+
+    /// Model ids advertised by `/models`
+    async fn available_models(&self) -> Result<Vec<String>, NeuralError> {
+        let response = self.client
+            .get(format!("{}/models", self.base_url))
+            .send()
+            .await
+            .map_err(|e| NeuralError::from_request(SERVICE, &self.base_url, "query models", e))?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+
+        let models = response.json::<ModelsResponse>().await
+            .map_err(|e| NeuralError::Parse(format!("Failed to parse model list: {}", e)))?;
+
+        Ok(models.data.into_iter().map(|model| model.id).collect())
+    }
+}
+
+#[async_trait]
+impl TranslationEngine for OpenAiClient {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    async fn translate(&self, request: TranslateRequest) -> Result<TranslateResponse, NeuralError> {
+        tracing::info!("Starting chat completion translation ({:?}): {} -> {}", request.style, request.from_lang, request.to_lang);
+
+        let model = self.resolve_model(&request);
+        let body = self.chat_body(&model, &build_messages(&request), false);
+
+        let completion = self.post_chat(&body).await?
+            .json::<ChatCompletionResponse>()
+            .await
+            .map_err(|e| NeuralError::Parse(format!("Failed to parse chat completion: {}", e)))?;
+
+        let content = completion.choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .ok_or_else(|| NeuralError::Parse("Chat completion contained no message".to_string()))?;
+
+        Ok(TranslateResponse {
+            translated_text: content.trim().to_string(),
+            model: if completion.model.is_empty() { model } else { completion.model },
+            engine: self.name().to_string(),
+            request_id: None,
+            direction: None,
+        })
+    }
+
+    /// Stream a translation over SSE, calling `on_chunk` with each content delta
+    async fn translate_stream(
+        &self,
+        request: TranslateRequest,
+        on_chunk: &mut ChunkSink<'_>,
+    ) -> Result<TranslateResponse, NeuralError> {
+        tracing::info!("🌊 Starting streaming chat completion ({:?}): {} -> {}", request.style, request.from_lang, request.to_lang);
+
+        let mut model = self.resolve_model(&request);
+        let body = self.chat_body(&model, &build_messages(&request), true);
+
+        let mut lines = LineStream::new(SERVICE, self.post_chat(&body).await?);
+        let mut translated_text = TrimmedDeltas::default();
+        let mut finished = false;
+
+        while let Some(line) = lines.next_line().await? {
+            let data = match parse_sse_line(&line) {
+                SseLine::Data(data) => data,
+                SseLine::Done => {
+                    finished = true;
+                    break;
+                }
+                SseLine::Ignored => continue,
+            };
+
+            if let Ok(error) = serde_json::from_str::<ErrorResponse>(data) {
+                return Err(NeuralError::Api {
+                    service: SERVICE.to_string(),
+                    status: 200,
+                    message: format!("Error while streaming: {}", error.error.message),
+                });
+            }
+
+            let chunk: ChatCompletionChunk = serde_json::from_str(data)
+                .map_err(|e| NeuralError::Parse(format!("Failed to parse streaming response: {}", e)))?;
+
+            if !chunk.model.is_empty() {
+                model = chunk.model;
+            }
+            for choice in chunk.choices {
+                if let Some(content) = choice.delta.content {
+                    translated_text.push(&content, on_chunk);
+                }
+                finished |= choice.finish_reason.is_some();
+            }
+        }
+
+        // A connection closed mid-answer would otherwise pass for a complete translation
+        if !finished {
+            return Err(NeuralError::Network("Stream ended before the translation was complete".to_string()));
+        }
+
+        Ok(TranslateResponse {
+            translated_text: translated_text.into_text(),
+            model,
+            engine: self.name().to_string(),
+            request_id: None,
+            direction: None,
+        })
+    }
+
+    async fn detect_language(&self, request: DetectLanguageRequest) -> Result<DetectLanguageResponse, NeuralError> {
+        Ok(detect_language_locally(&request))
+    }
+
+    async fn check_health(&self) -> HealthReport {
+        let models = match self.available_models().await {
+            Ok(models) => models,
+            Err(e) => {
+                tracing::warn!("OpenAI-compatible health check failed: {}", e);
+                return HealthReport::unreachable(self.name(), e);
+            }
+        };
+
+        let error = if models.is_empty() {
+            tracing::warn!("⚠ {} is reachable but has no model loaded", self.base_url);
+            Some(NeuralError::NoModelAvailable { models: Vec::new() })
+        } else {
+            tracing::info!("✓ OpenAI-compatible server is healthy: {}", models.join(", "));
+            None
+        };
+
+        HealthReport {
+            engine: self.name().to_string(),
+            healthy: error.is_none(),
+            reachable: true,
+            installed_models: models,
+            error,
+            ..HealthReport::default()
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, NeuralError> {
+        self.available_models().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompts::PromptStyle;
+    use crate::language::Language;
+    use crate::stub_server::{StubResponse, StubServer};
+
+    fn client_for(server: &StubServer, model: &str) -> OpenAiClient {
+        OpenAiClient::from_config(&OpenAiConfig {
+            base_url: format!("{}/v1", server.base_url),
+            api_key: Some("sk-local".to_string()),
+            model: model.to_string(),
+            timeout_secs: 5,
+        }).unwrap()
+    }
+
+    fn request(text: &str) -> TranslateRequest {
+        TranslateRequest {
+            text: text.to_string(),
+            from_lang: Language::English,
+            to_lang: Language::Japanese,
+            models: Vec::new(),
+            style: PromptStyle::Simple,
+            context: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_sse_line() {
+        assert_eq!(parse_sse_line("data: {\"a\":1}"), SseLine::Data("{\"a\":1}"));
+        assert_eq!(parse_sse_line("data:{\"a\":1}"), SseLine::Data("{\"a\":1}"));
+        assert_eq!(parse_sse_line("data: [DONE]"), SseLine::Done);
+        assert_eq!(parse_sse_line(": keep-alive"), SseLine::Ignored);
+        assert_eq!(parse_sse_line(""), SseLine::Ignored);
+    }
+
+    #[tokio::test]
+    async fn test_translate_sends_chat_messages() {
+        let server = StubServer::start(|_| StubResponse::json(200, json!({
+            "model": "qwen2.5-7b-instruct",
+            "choices": [{ "index": 0, "message": { "role": "assistant", "content": " こんにちは \n" } }]
+        }))).await;
+        let client = client_for(&server, "qwen2.5-7b-instruct");
+
+        let response = client.translate(request("Hello")).await.unwrap();
+        assert_eq!(response.translated_text, "こんにちは");
+        assert_eq!(response.model, "qwen2.5-7b-instruct");
+        assert_eq!(response.engine, "openai");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/v1/chat/completions");
+        assert_eq!(requests[0].header("authorization"), Some("Bearer sk-local"));
+
+        let body = requests[0].json();
+        assert_eq!(body["model"], "qwen2.5-7b-instruct");
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][1]["role"], "user");
+        assert_eq!(body["messages"][1]["content"], "<source>\nHello\n</source>");
+    }
+
+    #[tokio::test]
+    async fn test_translate_stream_reassembles_sse_deltas() {
+        let server = StubServer::start(|_| StubResponse::streamed("text/event-stream", vec![
+            "data: {\"model\":\"local\",\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n".to_string(),
+            "data: {\"choices\":[{\"delta\":{\"content\":\"こん\"}}]}\n\ndata: {\"choi".to_string(),
+            "ces\":[{\"delta\":{\"content\":\"にちは\"}}]}\n\n".to_string(),
+            ": keep-alive\n\ndata: [DONE]\n\n".to_string(),
+        ])).await;
+        let client = client_for(&server, "");
+
+        let mut chunks = Vec::new();
+        let response = client
+            .translate_stream(request("Hello"), &mut |delta: &str| chunks.push(delta.to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(chunks, vec!["こん", "にちは"]);
+        assert_eq!(response.translated_text, "こんにちは");
+        assert_eq!(response.model, "local");
+
+        let body = server.requests()[0].json();
+        assert_eq!(body["stream"], true);
+        assert!(body.get("model").is_none(), "Empty model should let the server pick its loaded model");
+    }
+
+    #[tokio::test]
+    async fn test_translate_stream_closed_before_done_is_an_error() {
+        let server = StubServer::start(|_| StubResponse::streamed("text/event-stream", vec![
+            "data: {\"choices\":[{\"delta\":{\"content\":\"変更を\"}}]}\n\n".to_string(),
+            "data: {\"choices\":[{\"delta\":{\"content\":\"保存\"}}]}\n\n".to_string(),
+        ])).await;
+        let client = client_for(&server, "");
+
+        let mut chunks = Vec::new();
+        let error = client
+            .translate_stream(request("Save changes"), &mut |delta: &str| chunks.push(delta.to_string()))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code(), "network_error");
+        assert_eq!(chunks, vec!["変更を", "保存"]);
+    }
+
+    #[tokio::test]
+    async fn test_translate_surfaces_api_error() {
+        let server = StubServer::start(|_| StubResponse::json(400, json!({
+            "error": { "message": "the request exceeds the available context size", "type": "invalid_request_error" }
+        }))).await;
+        let client = client_for(&server, "");
+
+        let error = client.translate(request("Hello")).await.unwrap_err();
+        assert_eq!(error.code(), "api_error");
+        assert!(error.to_string().contains("exceeds the available context size"), "Unexpected error: {}", error);
+    }
+
+    #[tokio::test]
+    async fn test_health_and_models() {
+        let server = StubServer::start(|request| match request.path.as_str() {
+            "/v1/models" => StubResponse::json(200, json!({
+                "object": "list",
+                "data": [{ "id": "gemma-3-4b-it", "object": "model" }]
+            })),
+            _ => StubResponse::text(404, "not found"),
+        }).await;
+        let client = client_for(&server, "");
+
+        assert_eq!(client.list_models().await.unwrap(), vec!["gemma-3-4b-it"]);
+        assert!(client.check_health().await.healthy);
+    }
+}
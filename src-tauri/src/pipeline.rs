@@ -0,0 +1,348 @@
+use serde::Serialize;
+
+use crate::chunking::{split_chunks, Segment};
+use crate::config::DirectionPreferences;
+use crate::engine::{ChunkSink, DetectLanguageRequest, TranslateRequest, TranslateResponse, TranslationContext, TranslationDirection, TranslationEngine};
+use crate::error::NeuralError;
+use crate::language::{Language, LanguageChoice};
+use crate::prompts::PromptStyle;
+
+// ===== Automatic Direction =====
+
+/// A translation as a command received it, before `auto` languages are resolved
+#[derive(Debug)]
+pub struct PendingTranslation {
+    pub text: String,
+    pub from_lang: LanguageChoice,
+    pub to_lang: LanguageChoice,
+    pub models: Vec<String>,
+    pub style: PromptStyle,
+}
+
+impl PendingTranslation {
+    /// Settle both languages: an `auto` source is detected with `engine`, and an `auto` target
+    /// becomes the first preferred language that isn't the source (or the source itself when
+    /// improving text). Explicit languages are kept.
+    pub async fn resolve(
+        self,
+        engine: &dyn TranslationEngine,
+        preferences: &DirectionPreferences,
+    ) -> Result<TranslateRequest, NeuralError> {
+        let from_lang = match self.from_lang {
+            LanguageChoice::Language(language) => language,
+            LanguageChoice::Auto => detect_source(engine, &self.text).await?,
+        };
+
+        let to_lang = match self.to_lang {
+            LanguageChoice::Language(language) => language,
+            LanguageChoice::Auto if self.style == PromptStyle::Improve => from_lang, // Polishing never changes the language
+            LanguageChoice::Auto => preferences
+                .target_for(from_lang)
+                .ok_or_else(|| NeuralError::InvalidInput("No target language configured for automatic translation".to_string()))?,
+        };
+
+        Ok(TranslateRequest {
+            text: self.text,
+            from_lang,
+            to_lang,
+            models: self.models,
+            style: self.style,
+            context: None,
+        })
+    }
+}
+
+async fn detect_source(engine: &dyn TranslationEngine, text: &str) -> Result<Language, NeuralError> {
+    let detected = engine
+        .detect_language(DetectLanguageRequest { text: text.to_string(), top_k: Some(1) })
+        .await?;
+    if detected.confidence <= 0.0 {
+        return Err(NeuralError::InvalidInput("Could not detect the language of the text; choose a source language".to_string()));
+    }
+    Ok(detected.language)
+}
+
+// ===== Chunked Document Translation =====
+
+/// Source tokens per chunk, leaving room in the output budget for translations longer than their source
+pub const CHUNK_TOKEN_BUDGET: usize = 400;
+
+/// Reported each time a chunk of the document finishes translating
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct ChunkProgress {
+    pub completed_chunks: usize,
+    pub total_chunks: usize,
+}
+
+/// Receives progress after every chunk
+pub type ProgressSink<'a> = dyn FnMut(ChunkProgress) + Send + 'a;
+
+/// Translate text of any length through `engine`, one chunk of at most `max_tokens` at a time.
+/// Whitespace between chunks is copied verbatim, each chunk sees the previous one as context,
+/// and streamed deltas (spacing included) reach `on_chunk` in document order.
+pub async fn translate_chunked(
+    engine: &dyn TranslationEngine,
+    request: TranslateRequest,
+    max_tokens: usize,
+    mut on_chunk: Option<&mut ChunkSink<'_>>,
+    on_progress: &mut ProgressSink<'_>,
+) -> Result<TranslateResponse, NeuralError> {
+    let segments = split_chunks(&request.text, max_tokens);
+    let total_chunks = segments.iter().filter(|segment| matches!(segment, Segment::Text(_))).count();
+    if total_chunks == 0 {
+        return Err(NeuralError::InvalidInput("Nothing to translate".to_string()));
+    }
+
+    let direction = TranslationDirection {
+        from_lang: request.from_lang,
+        to_lang: request.to_lang,
+    };
+    let mut models = request.models.clone();
+    let mut context: Option<TranslationContext> = None;
+    let mut translated_text = String::new();
+    let mut model = String::new();
+    let mut completed_chunks = 0;
+
+    for segment in segments {
+        let chunk = match segment {
+            Segment::Spacing(spacing) => {
+                translated_text.push_str(spacing);
+                if let Some(on_chunk) = on_chunk.as_deref_mut() {
+                    on_chunk(spacing);
+                }
+                continue;
+            }
+            Segment::Text(chunk) => chunk,
+        };
+
+        let chunk_request = TranslateRequest {
+            text: chunk.to_string(),
+            from_lang: request.from_lang,
+            to_lang: request.to_lang,
+            models: models.clone(),
+            style: request.style,
+            context: context.take(),
+        };
+        let response = match on_chunk.as_deref_mut() {
+            Some(on_chunk) => engine.translate_stream(chunk_request, on_chunk).await?,
+            None => engine.translate(chunk_request).await?,
+        };
+
+        // Later chunks go straight to the model that answered instead of walking the fallbacks again
+        if models.len() > 1 && !response.model.is_empty() {
+            models = vec![response.model.clone()];
+        }
+
+        translated_text.push_str(&response.translated_text);
+        completed_chunks += 1;
+        on_progress(ChunkProgress { completed_chunks, total_chunks });
+
+        context = Some(TranslationContext {
+            source: chunk.to_string(),
+            translation: response.translated_text,
+        });
+        model = response.model;
+    }
+
+    Ok(TranslateResponse {
+        translated_text,
+        model,
+        engine: engine.name().to_string(),
+        request_id: None,
+        direction: Some(direction),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{detect_language_locally, DetectLanguageResponse, HealthReport};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    struct SeenRequest {
+        models: Vec<String>,
+        context_source: Option<String>,
+    }
+
+    /// Upper-cases its input, answering only for `qwen2.5:3b`, and records every request it sees
+    #[derive(Default)]
+    struct UppercaseEngine {
+        seen: Mutex<Vec<SeenRequest>>,
+    }
+
+    #[async_trait]
+    impl TranslationEngine for UppercaseEngine {
+        fn name(&self) -> &'static str {
+            "uppercase"
+        }
+
+        async fn translate(&self, request: TranslateRequest) -> Result<TranslateResponse, NeuralError> {
+            self.seen.lock().unwrap().push(SeenRequest {
+                models: request.models.clone(),
+                context_source: request.context.as_ref().map(|context| context.source.clone()),
+            });
+
+            let model = request.models.iter()
+                .find(|model| model.as_str() == "qwen2.5:3b")
+                .cloned()
+                .ok_or_else(|| NeuralError::NoModelAvailable { models: request.models.clone() })?;
+
+            Ok(TranslateResponse {
+                translated_text: request.text.to_uppercase(),
+                model,
+                engine: self.name().to_string(),
+                request_id: None,
+                direction: None,
+            })
+        }
+
+        async fn detect_language(&self, request: DetectLanguageRequest) -> Result<DetectLanguageResponse, NeuralError> {
+            Ok(detect_language_locally(&request))
+        }
+
+        async fn check_health(&self) -> HealthReport {
+            HealthReport { healthy: true, reachable: true, ..HealthReport::default() }
+        }
+
+        async fn list_models(&self) -> Result<Vec<String>, NeuralError> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn request(text: &str) -> TranslateRequest {
+        TranslateRequest {
+            text: text.to_string(),
+            from_lang: Language::English,
+            to_lang: Language::English,
+            models: vec!["aya:8b".to_string(), "qwen2.5:3b".to_string()],
+            style: PromptStyle::Simple,
+            context: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_every_paragraph_is_translated_with_original_spacing() {
+        let engine = UppercaseEngine::default();
+        let mut progress = Vec::new();
+
+        let response = translate_chunked(
+            &engine,
+            request("first paragraph\nwith two lines\n\n\nsecond\n\nthird\n"),
+            CHUNK_TOKEN_BUDGET,
+            None,
+            &mut |update| progress.push(update),
+        ).await.unwrap();
+
+        assert_eq!(response.translated_text, "FIRST PARAGRAPH\nWITH TWO LINES\n\n\nSECOND\n\nTHIRD\n");
+        assert_eq!(response.model, "qwen2.5:3b");
+        assert_eq!(response.engine, "uppercase");
+        assert_eq!(progress.last(), Some(&ChunkProgress { completed_chunks: 3, total_chunks: 3 }));
+        assert_eq!(progress.len(), 3);
+        assert_eq!(response.direction, Some(TranslationDirection {
+            from_lang: Language::English,
+            to_lang: Language::English,
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_chunks_carry_previous_context_and_settled_model() {
+        let engine = UppercaseEngine::default();
+
+        translate_chunked(
+            &engine,
+            request("One two three four. Five six seven eight. Nine ten eleven twelve."),
+            6,
+            None,
+            &mut |_| {},
+        ).await.unwrap();
+
+        let seen = engine.seen.lock().unwrap();
+        assert_eq!(seen.len(), 3);
+        assert_eq!(seen[0].models, vec!["aya:8b", "qwen2.5:3b"]);
+        assert_eq!(seen[0].context_source, None);
+        assert_eq!(seen[1].models, vec!["qwen2.5:3b"]);
+        assert_eq!(seen[1].context_source.as_deref(), Some("One two three four."));
+        assert_eq!(seen[2].context_source.as_deref(), Some("Five six seven eight."));
+    }
+
+    #[tokio::test]
+    async fn test_streamed_deltas_include_spacing_in_order() {
+        let engine = UppercaseEngine::default();
+        let mut deltas = Vec::new();
+
+        let response = translate_chunked(
+            &engine,
+            request("a\n\nb"),
+            CHUNK_TOKEN_BUDGET,
+            Some(&mut |delta: &str| deltas.push(delta.to_string())),
+            &mut |_| {},
+        ).await.unwrap();
+
+        assert_eq!(deltas, vec!["A", "\n\n", "B"]);
+        assert_eq!(response.translated_text, "A\n\nB");
+    }
+
+    #[tokio::test]
+    async fn test_blank_input_is_rejected() {
+        let engine = UppercaseEngine::default();
+        let result = translate_chunked(&engine, request(" \n\n "), CHUNK_TOKEN_BUDGET, None, &mut |_| {}).await;
+
+        assert!(result.is_err());
+        assert!(engine.seen.lock().unwrap().is_empty());
+    }
+
+    async fn resolved(text: &str, from_lang: &str, to_lang: &str, style: PromptStyle) -> Result<(Language, Language), NeuralError> {
+        let pending = PendingTranslation {
+            text: text.to_string(),
+            from_lang: LanguageChoice::parse(from_lang)?,
+            to_lang: LanguageChoice::parse(to_lang)?,
+            models: Vec::new(),
+            style,
+        };
+        let request = pending.resolve(&UppercaseEngine::default(), &DirectionPreferences::default()).await?;
+        Ok((request.from_lang, request.to_lang))
+    }
+
+    #[tokio::test]
+    async fn test_auto_direction_follows_preferences() {
+        assert_eq!(
+            resolved("今日はとても良い天気ですね。", "auto", "auto", PromptStyle::Simple).await.unwrap(),
+            (Language::Japanese, Language::English)
+        );
+        assert_eq!(
+            resolved("The weather is lovely today.", "Auto", "", PromptStyle::Simple).await.unwrap(),
+            (Language::English, Language::Japanese)
+        );
+        assert_eq!(
+            resolved("Il fait très beau aujourd'hui, n'est-ce pas ?", "auto", "auto", PromptStyle::Simple).await.unwrap(),
+            (Language::French, Language::Japanese)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_explicit_languages_are_kept() {
+        assert_eq!(
+            resolved("The weather is lovely today.", "auto", "de", PromptStyle::Simple).await.unwrap(),
+            (Language::English, Language::German)
+        );
+        assert_eq!(
+            resolved("anything", "Korean", "auto", PromptStyle::Simple).await.unwrap(),
+            (Language::Korean, Language::Japanese)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_auto_improvement_keeps_the_detected_language() {
+        assert_eq!(
+            resolved("Das Wetter ist heute wirklich schön.", "auto", "auto", PromptStyle::Improve).await.unwrap(),
+            (Language::German, Language::German)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_undetectable_source_is_rejected() {
+        let error = resolved("12:30 - 13:00", "auto", "auto", PromptStyle::Simple).await.unwrap_err();
+        assert_eq!(error.code(), "invalid_input");
+    }
+}
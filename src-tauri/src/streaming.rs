@@ -0,0 +1,206 @@
+use serde::de::DeserializeOwned;
+
+use crate::engine::ChunkSink;
+use crate::error::NeuralError;
+
+// ===== Line-oriented Response Streaming =====
+
+/// Incremental line reader over a streaming HTTP response body.
+/// A single line may be split across network chunks, so bytes are buffered until a newline arrives.
+pub struct LineStream {
+    service: &'static str, // Named in timeout errors
+    response: reqwest::Response,
+    buffer: Vec<u8>,
+    finished: bool,
+}
+
+impl LineStream {
+    pub fn new(service: &'static str, response: reqwest::Response) -> Self {
+        Self {
+            service,
+            response,
+            buffer: Vec::new(),
+            finished: false,
+        }
+    }
+
+    /// Next line without its terminator (`\n` or `\r\n`), or `None` once the body is exhausted
+    pub async fn next_line(&mut self) -> Result<Option<String>, NeuralError> {
+        loop {
+            if let Some(newline) = self.buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=newline).collect();
+                return Ok(Some(decode_line(&line)));
+            }
+
+            if self.finished {
+                // The final line is not always newline-terminated
+                if self.buffer.is_empty() {
+                    return Ok(None);
+                }
+                let line = std::mem::take(&mut self.buffer);
+                return Ok(Some(decode_line(&line)));
+            }
+
+            match self.response.chunk().await {
+                Ok(Some(bytes)) => self.buffer.extend_from_slice(&bytes),
+                Ok(None) => self.finished = true,
+                Err(e) if e.is_timeout() => return Err(NeuralError::Timeout { service: self.service.to_string() }),
+                Err(e) => return Err(NeuralError::Network(format!("Response stream was interrupted: {}", e))),
+            }
+        }
+    }
+}
+
+fn decode_line(line: &[u8]) -> String {
+    String::from_utf8_lossy(line)
+        .trim_end_matches(['\n', '\r'])
+        .to_string()
+}
+
+/// Reader for newline-delimited JSON responses such as Ollama's streaming endpoints
+pub struct NdjsonStream {
+    lines: LineStream,
+}
+
+impl NdjsonStream {
+    pub fn new(service: &'static str, response: reqwest::Response) -> Self {
+        Self {
+            lines: LineStream::new(service, response),
+        }
+    }
+
+    /// Next object in the stream, or `None` once the response body is exhausted
+    pub async fn next<T: DeserializeOwned>(&mut self) -> Result<Option<T>, NeuralError> {
+        while let Some(line) = self.lines.next_line().await? {
+            if let Some(item) = parse_ndjson_line(&line)? {
+                return Ok(Some(item));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Parse one NDJSON line; blank lines yield `None`
+fn parse_ndjson_line<T: DeserializeOwned>(line: &str) -> Result<Option<T>, NeuralError> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    serde_json::from_str(line)
+        .map(Some)
+        .map_err(|e| NeuralError::Parse(format!("Failed to parse streaming response: {}", e)))
+}
+
+// ===== Streamed Text =====
+
+/// Forwards streamed text so the deltas add up to the trimmed translation a non-streaming call returns:
+/// leading whitespace is dropped and trailing whitespace is held back until more text follows it.
+#[derive(Default)]
+pub struct TrimmedDeltas {
+    text: String,    // Everything forwarded so far
+    pending: String, // Whitespace that may still turn out to be trailing
+}
+
+impl TrimmedDeltas {
+    pub fn push(&mut self, delta: &str, on_chunk: &mut ChunkSink<'_>) {
+        self.pending.push_str(delta);
+        if self.text.is_empty() {
+            let leading = self.pending.len() - self.pending.trim_start().len();
+            self.pending.drain(..leading);
+        }
+
+        let ready = self.pending.trim_end().len();
+        if ready > 0 {
+            let ready: String = self.pending.drain(..ready).collect();
+            on_chunk(&ready);
+            self.text.push_str(&ready);
+        }
+    }
+
+    /// Text forwarded so far
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_text(self) -> String {
+        self.text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use crate::stub_server::{StubResponse, StubServer};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        n: u32,
+    }
+
+    /// Body sent in `parts`, one network write each; the server must outlive the read
+    async fn body(parts: &[&str]) -> (StubServer, reqwest::Response) {
+        let parts: Vec<String> = parts.iter().map(|part| part.to_string()).collect();
+        let server = StubServer::start(move |_| StubResponse::streamed("application/x-ndjson", parts.clone())).await;
+        let response = reqwest::get(&server.base_url).await.unwrap();
+        (server, response)
+    }
+
+    async fn lines(parts: &[&str]) -> Vec<String> {
+        let (_server, response) = body(parts).await;
+        let mut stream = LineStream::new("Test", response);
+        let mut lines = Vec::new();
+        while let Some(line) = stream.next_line().await.unwrap() {
+            lines.push(line);
+        }
+        lines
+    }
+
+    #[tokio::test]
+    async fn test_line_split_across_chunks() {
+        assert_eq!(lines(&["{\"n\":", "1}\n{\"n\"", ":2}\n"]).await, vec!["{\"n\":1}", "{\"n\":2}"]);
+    }
+
+    #[tokio::test]
+    async fn test_crlf_line_endings() {
+        assert_eq!(lines(&["one\r\ntw", "o\r", "\n"]).await, vec!["one", "two"]);
+    }
+
+    #[tokio::test]
+    async fn test_final_line_without_newline() {
+        assert_eq!(lines(&["one\n", "two"]).await, vec!["one", "two"]);
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_skips_blank_lines() {
+        let (_server, response) = body(&["{\"n\":1}\n\n", "  \r\n{\"n\":2}\n", "\n"]).await;
+        let mut stream = NdjsonStream::new("Test", response);
+
+        assert_eq!(stream.next::<Item>().await.unwrap(), Some(Item { n: 1 }));
+        assert_eq!(stream.next::<Item>().await.unwrap(), Some(Item { n: 2 }));
+        assert_eq!(stream.next::<Item>().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_invalid_line_is_a_parse_error() {
+        let (_server, response) = body(&["{\"n\":1}\n", "not json\n", "{\"n\":3}\n"]).await;
+        let mut stream = NdjsonStream::new("Test", response);
+
+        assert_eq!(stream.next::<Item>().await.unwrap(), Some(Item { n: 1 }));
+        assert_eq!(stream.next::<Item>().await.unwrap_err().code(), "parse_error");
+    }
+
+    #[test]
+    fn test_trimmed_deltas_add_up_to_the_trimmed_text() {
+        let parts = ["\n ", " こん", "にち ", "\n", "は ", " \n"];
+        let mut trimmed = TrimmedDeltas::default();
+        let mut deltas = Vec::new();
+        for part in parts {
+            trimmed.push(part, &mut |delta: &str| deltas.push(delta.to_string()));
+        }
+
+        assert_eq!(deltas, vec!["こん", "にち", " \nは"]);
+        assert_eq!(trimmed.into_text(), parts.concat().trim());
+    }
+}
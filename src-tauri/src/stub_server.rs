@@ -0,0 +1,235 @@
+//! Minimal in-process HTTP server for exercising engine clients in tests.
+//!
+//! Each connection serves exactly one request and is then closed, so handlers see
+//! requests in the order the client sends them.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// A request as received by the stub, with lowercased header names
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.headers
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_str(&self.body).expect("request body should be JSON")
+    }
+}
+
+/// Canned response. A body with several parts is sent with chunked encoding,
+/// one part per write, so clients see it arrive in pieces.
+#[derive(Debug, Clone)]
+pub struct StubResponse {
+    pub status: u16,
+    pub content_type: String,
+    pub body_parts: Vec<String>,
+    pub delay: Duration, // Wait before answering, to simulate a hung server
+    pub pause: Duration, // Wait between body parts, to simulate a slow or stalled stream
+}
+
+impl StubResponse {
+    pub fn json(status: u16, body: serde_json::Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json".to_string(),
+            body_parts: vec![body.to_string()],
+            delay: Duration::ZERO,
+            pause: Duration::ZERO,
+        }
+    }
+
+    pub fn text(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain".to_string(),
+            body_parts: vec![body.to_string()],
+            delay: Duration::ZERO,
+            pause: Duration::ZERO,
+        }
+    }
+
+    pub fn streamed(content_type: &str, parts: Vec<String>) -> Self {
+        Self {
+            status: 200,
+            content_type: content_type.to_string(),
+            body_parts: parts,
+            delay: Duration::ZERO,
+            pause: Duration::ZERO,
+        }
+    }
+
+    pub fn delayed(self, delay: Duration) -> Self {
+        Self { delay, ..self }
+    }
+
+    pub fn paced(self, pause: Duration) -> Self {
+        Self { pause, ..self }
+    }
+}
+
+type Handler = dyn Fn(&RecordedRequest) -> StubResponse + Send + Sync;
+
+pub struct StubServer {
+    pub base_url: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    task: JoinHandle<()>,
+}
+
+impl StubServer {
+    /// Bind to an ephemeral localhost port and answer every request with `handler`
+    pub async fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&RecordedRequest) -> StubResponse + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind stub server");
+        let base_url = format!("http://{}", listener.local_addr().expect("stub server address"));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let task = {
+            let requests = Arc::clone(&requests);
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let requests = Arc::clone(&requests);
+                    let handler = Arc::clone(&handler);
+                    tokio::spawn(async move {
+                        let _ = serve_connection(stream, requests, handler).await;
+                    });
+                }
+            })
+        };
+
+        Self { base_url, requests, task }
+    }
+
+    /// Every request received so far, in arrival order
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for StubServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve_connection(
+    mut stream: TcpStream,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    handler: Arc<Handler>,
+) -> std::io::Result<()> {
+    let request = read_request(&mut stream).await?;
+    requests.lock().unwrap().push(request.clone());
+    let response = handler(&request);
+    tokio::time::sleep(response.delay).await;
+
+    let chunked = response.body_parts.len() > 1;
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nConnection: close\r\n",
+        response.status,
+        reason_phrase(response.status),
+        response.content_type
+    );
+    if chunked {
+        head.push_str("Transfer-Encoding: chunked\r\n\r\n");
+    } else {
+        let length: usize = response.body_parts.iter().map(String::len).sum();
+        head.push_str(&format!("Content-Length: {}\r\n\r\n", length));
+    }
+    stream.write_all(head.as_bytes()).await?;
+
+    if chunked {
+        for part in &response.body_parts {
+            stream.write_all(format!("{:x}\r\n{}\r\n", part.len(), part).as_bytes()).await?;
+            stream.flush().await?;
+            // Give the client a chance to read each part separately
+            tokio::time::sleep(response.pause.max(Duration::from_millis(5))).await;
+        }
+        stream.write_all(b"0\r\n\r\n").await?;
+    } else {
+        for part in &response.body_parts {
+            stream.write_all(part.as_bytes()).await?;
+        }
+    }
+
+    stream.flush().await?;
+    stream.shutdown().await
+}
+
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<RecordedRequest> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        if let Some(position) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break position + 4;
+        }
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "connection closed before headers"));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let content_length = headers
+        .iter()
+        .find(|(key, _)| key == "content-length")
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let mut body = buffer[header_end..].to_vec();
+    while body.len() < content_length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+
+    Ok(RecordedRequest {
+        method,
+        path,
+        headers,
+        body: String::from_utf8_lossy(&body).to_string(),
+    })
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Status",
+    }
+}
@@ -7,13 +7,42 @@ import SettingsButton from "./components/SettingsButton";
 import SettingsScreen from "./components/SettingsScreen";
 import LanguageSetupModal from "./components/LanguageSetupModal";
 import HistoryScreen from "./components/HistoryScreen";
+import { isNeuralError, type NeuralError } from "./errors";
+import { languageName } from "./languages";
+
+// Languages are BCP 47 tags such as "ja" or "zh-TW"; see languageName()
+interface TranslationDirection {
+  from_lang: string;
+  to_lang: string;
+}
 
 interface TranslateResponse {
   translated_text: string;
+  model?: string;
+  engine?: string;
+  direction?: TranslationDirection | null;
+}
+
+interface LanguageCandidate {
+  language: string;
+  confidence: number;
 }
 
 interface DetectLanguageResponse {
   language: string;
+  confidence: number;
+  candidates: LanguageCandidate[];
+}
+
+interface HealthReport {
+  engine: string;
+  healthy: boolean;
+  reachable: boolean;
+  version?: string;
+  installed_models: string[];
+  missing_models: { name: string; pull_command: string }[];
+  probe_latency_ms?: number;
+  error?: NeuralError;
 }
 
 // ML Engine interfaces (M4 Mac optimized)
@@ -33,12 +62,26 @@ const languages = [
   { code: 'Japanese', name: '日本語', flag: '🇯🇵' },
   { code: 'English', name: 'English', flag: '🇺🇸' },
   { code: 'Chinese', name: '中文', flag: '🇨🇳' },
+  { code: 'Traditional Chinese', name: '繁體中文', flag: '🇹🇼' },
   { code: 'Korean', name: '한국어', flag: '🇰🇷' },
   { code: 'Spanish', name: 'Español', flag: '🇪🇸' },
   { code: 'French', name: 'Français', flag: '🇫🇷' },
   { code: 'German', name: 'Deutsch', flag: '🇩🇪' },
 ];
 
+// Status badge tooltip: version and probe latency when healthy, otherwise the problem and how to fix it
+function healthTooltip(report?: HealthReport): string | undefined {
+  if (!report) return undefined;
+  if (report.healthy) {
+    return `Ollama ${report.version ?? ''} · ${report.installed_models.join(', ')} · ${report.probe_latency_ms ?? '-'}ms`;
+  }
+  const lines = [report.error?.message ?? 'Ollamaが利用できません'];
+  if (report.reachable && report.installed_models.length === 0) {
+    lines.push(...report.missing_models.map(model => model.pull_command));
+  }
+  return lines.join('\n');
+}
+
 function App() {
   const [inputText, setInputText] = useState("");
   const [translatedText, setTranslatedText] = useState("");
@@ -48,6 +91,7 @@ function App() {
   });
   const [isTranslating, setIsTranslating] = useState(false);
   const [isHealthy, setIsHealthy] = useState(false);
+  const [healthReport, setHealthReport] = useState<HealthReport | undefined>();
   const [mlEngineHealthy, setMlEngineHealthy] = useState(false);
   const [useMLEngine, setUseMLEngine] = useState(false); // Default to Ollama until ML engine is implemented
   const [autoTranslate, setAutoTranslate] = useState(false);
@@ -145,8 +189,12 @@ function App() {
 
   async function checkOllamaHealth() {
     try {
-      const healthy = await invoke<boolean>("check_ollama_health");
-      setIsHealthy(healthy);
+      const report = await invoke<HealthReport>("check_ollama_health");
+      setHealthReport(report);
+      setIsHealthy(report.healthy);
+      if (!report.healthy) {
+        console.warn("Ollama is not ready:", report.error?.message, report.missing_models.map(model => model.pull_command));
+      }
     } catch (error) {
       console.error("Failed to check Ollama health:", error);
       setIsHealthy(false);
@@ -184,7 +232,7 @@ function App() {
         detectResponse = await invoke<DetectLanguageResponse>("detect_language", { text });
       }
       
-      const detectedLang = detectResponse.language;
+      const detectedLang = languageName(detectResponse.language);
       
       // Update from language if it was Auto
       if (fromLang === "Auto") {
@@ -231,6 +279,8 @@ function App() {
       
       // Choose the best available engine for translation
       let translatedTextResult: string;
+      let usedModel: string | undefined;
+      let usedEngine = 'ollama';
       let latency = 0;
       
       if (useMLEngine && mlEngineHealthy) {
@@ -254,6 +304,8 @@ function App() {
           toLang: actualToLang,
         });
         translatedTextResult = response.translated_text;
+        usedModel = response.model || undefined;
+        usedEngine = response.engine || 'ollama';
         latency = Math.round(performance.now() - startTime);
         setLastTranslationLatency(latency);
         setLastUsedEngine('ollama');
@@ -275,8 +327,9 @@ function App() {
             translatedText: translatedTextResult,
             fromLanguage: actualFromLang,
             toLanguage: actualToLang,
-            engine: useMLEngine && mlEngineHealthy ? 'ml' : 'ollama',
+            engine: useMLEngine && mlEngineHealthy ? 'ml' : usedEngine,
             latencyMs: latency > 0 ? latency : undefined,
+            model: usedModel,
             historyPath,
           });
           console.log('📚 Translation saved to history');
@@ -326,8 +379,9 @@ function App() {
                 translatedText: response.translated_text,
                 fromLanguage: actualFromLang,
                 toLanguage: actualToLang,
-                engine: 'ollama',
+                engine: response.engine || 'ollama',
                 latencyMs: latency > 0 ? latency : undefined,
+                model: response.model || undefined,
                 historyPath,
               });
               console.log('📚 Fallback translation saved to history');
@@ -343,15 +397,25 @@ function App() {
       }
       
       // Both engines failed, show appropriate error message
-      if (typeof error === 'string') {
-        if (error.includes("Cannot connect to Ollama")) {
-          errorMessage = "Ollamaに接続できません。Ollamaが起動していることを確認してください。\n\n起動方法: ollama serve";
-        } else if (error.includes("No suitable model available")) {
-          errorMessage = "適切なモデルが見つかりません。\n\n以下のコマンドでモデルをインストールしてください:\nollama pull llama3.1:8b";
-        } else if (error.includes("model")) {
-          errorMessage = "モデルの読み込みエラーです。しばらく待ってから再試行してください。";
-        } else {
-          errorMessage = `エラー: ${error}`;
+      if (isNeuralError(error)) {
+        switch (error.code) {
+          case "connection_refused":
+            errorMessage = "Ollamaに接続できません。Ollamaが起動していることを確認してください。\n\n起動方法: ollama serve";
+            break;
+          case "no_model_available":
+            errorMessage = "適切なモデルが見つかりません。\n\n以下のコマンドでモデルをインストールしてください:\nollama pull llama3.1:8b";
+            break;
+          case "model_not_found":
+            errorMessage = "モデルの読み込みエラーです。しばらく待ってから再試行してください。";
+            break;
+          case "timeout":
+            errorMessage = "翻訳がタイムアウトしました。しばらく待ってから再試行してください。";
+            break;
+          case "unsupported_language":
+            errorMessage = `対応していない言語です。\n\n${error.message}`;
+            break;
+          default:
+            errorMessage = `エラー: ${error.message}`;
         }
       } else if (!isHealthy && !mlEngineHealthy) {
         errorMessage = "翻訳エンジンが利用できません。\n\nOllamaを起動してください:\nollama serve";
@@ -433,7 +497,7 @@ function App() {
               {/* Ollama Status */}
               <div className={`flex items-center gap-2 px-3 py-1.5 rounded-full text-sm ${
                 isHealthy ? 'bg-green-100 text-green-700' : 'bg-red-100 text-red-700'
-              }`}>
+              }`} title={healthTooltip(healthReport)}>
                 <div className={`w-2 h-2 rounded-full ${
                   isHealthy ? 'bg-green-500' : 'bg-red-500'
                 } ${isHealthy ? 'animate-pulse' : ''}`} />
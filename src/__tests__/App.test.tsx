@@ -18,6 +18,17 @@ import { listen } from '@tauri-apps/api/event'
 const mockInvoke = vi.mocked(invoke)
 const mockListen = vi.mocked(listen)
 
+// Ollama health report for a running server with a model ready
+const healthyReport = {
+  engine: 'ollama',
+  healthy: true,
+  reachable: true,
+  version: '0.6.2',
+  installed_models: ['qwen2.5:3b'],
+  missing_models: [],
+  probe_latency_ms: 120,
+}
+
 // Mock navigator.clipboard
 const mockWriteText = vi.fn().mockResolvedValue(undefined)
 
@@ -69,11 +80,11 @@ describe('App Integration Tests', () => {
     mockInvoke.mockImplementation((command: string) => {
       switch (command) {
         case 'check_ollama_health':
-          return Promise.resolve(true)
+          return Promise.resolve(healthyReport)
         case 'check_ml_health':
           return Promise.resolve(false) // ML engine not available (placeholder)
         case 'detect_language':
-          return Promise.resolve({ language: 'English' })
+          return Promise.resolve({ language: 'en', confidence: 0.98, candidates: [] })
         case 'translate':
           return Promise.resolve({ translated_text: 'こんにちは' })
         case 'get_clipboard_text':
@@ -220,11 +231,55 @@ describe('App Integration Tests', () => {
     }, { timeout: 1500 })
   })
 
+  it('translates detected Traditional Chinese as Traditional Chinese', async () => {
+    const user = userEvent.setup()
+    mockInvoke.mockImplementation((command: string) => {
+      switch (command) {
+        case 'check_ollama_health':
+          return Promise.resolve(healthyReport)
+        case 'check_ml_health':
+          return Promise.resolve(false)
+        case 'detect_language':
+          return Promise.resolve({ language: 'zh-TW', confidence: 0.97, candidates: [] })
+        case 'translate':
+          return Promise.resolve({ translated_text: '今日はいい天気です' })
+        default:
+          return Promise.resolve()
+      }
+    })
+    
+    await act(async () => {
+      render(<App />)
+    })
+    
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith('check_ollama_health')
+    })
+    
+    const textarea = screen.getByPlaceholderText('翻訳したいテキストを入力またはペーストしてください')
+    
+    await act(async () => {
+      await user.type(textarea, '今天天氣很好')
+    })
+    
+    // "Chinese" would be read back as Simplified Chinese, so the detected tag must keep its own name
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith('translate', expect.objectContaining({
+        fromLang: 'Traditional Chinese',
+        toLang: 'Japanese',
+      }))
+      expect(mockInvoke).toHaveBeenCalledWith('save_translation_history', expect.objectContaining({
+        fromLanguage: 'Traditional Chinese',
+      }))
+    }, { timeout: 1500 })
+    expect(mockInvoke).not.toHaveBeenCalledWith('translate', expect.objectContaining({ fromLang: 'Chinese' }))
+  })
+
   it('displays health status', async () => {
     mockInvoke.mockImplementation((command: string) => {
       switch (command) {
         case 'check_ollama_health':
-          return Promise.resolve(true)
+          return Promise.resolve(healthyReport)
         case 'check_ml_health':
           return Promise.resolve(false)
         default:
@@ -288,7 +343,7 @@ describe('App Integration Tests', () => {
     // Setup specific mock for this test - need to let health check succeed first
     mockInvoke.mockImplementation((command: string) => {
       if (command === 'check_ollama_health') {
-        return Promise.resolve(true)
+        return Promise.resolve(healthyReport)
       }
       if (command === 'check_ml_health') {
         return Promise.resolve(false) // ML not available
@@ -370,9 +425,9 @@ describe('App Integration Tests', () => {
     mockInvoke.mockImplementation((command: string) => {
       switch (command) {
         case 'check_ollama_health':
-          return Promise.resolve(true)
+          return Promise.resolve(healthyReport)
         case 'detect_language':
-          return Promise.resolve({ language: 'English' })
+          return Promise.resolve({ language: 'en', confidence: 0.98, candidates: [] })
         case 'translate':
           return Promise.resolve({ translated_text: translatedText })
         default:
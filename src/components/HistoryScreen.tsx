@@ -1,5 +1,6 @@
 import { useState, useEffect } from 'react';
 import { invoke } from "@tauri-apps/api/core";
+import { languageName } from '../languages';
 
 interface HistoryScreenProps {
   onBack: () => void;
@@ -92,13 +93,14 @@ export default function HistoryScreen({ onBack, onLoadTranslation }: HistoryScre
       'Japanese': '🇯🇵',
       'English': '🇺🇸',
       'Chinese': '🇨🇳',
+      'Traditional Chinese': '🇹🇼',
       'Korean': '🇰🇷',
       'Spanish': '🇪🇸',
       'French': '🇫🇷',
       'German': '🇩🇪',
       'Auto': '🌐',
     };
-    return flags[language] || '🌍';
+    return flags[languageName(language)] || '🌍';
   };
 
   const getEngineIcon = (engine: string) => {
@@ -8,6 +8,7 @@ const languages = [
   { code: 'Japanese', name: '日本語', flag: '🇯🇵' },
   { code: 'English', name: 'English', flag: '🇺🇸' },
   { code: 'Chinese', name: '中文', flag: '🇨🇳' },
+  { code: 'Traditional Chinese', name: '繁體中文', flag: '🇹🇼' },
   { code: 'Korean', name: '한국어', flag: '🇰🇷' },
   { code: 'Spanish', name: 'Español', flag: '🇪🇸' },
   { code: 'French', name: 'Français', flag: '🇫🇷' },
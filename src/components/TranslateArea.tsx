@@ -1,5 +1,6 @@
 import { useState, useRef } from 'react';
 import { invoke } from "@tauri-apps/api/core";
+import { describeError } from "../errors";
 
 interface TranslateAreaProps {
   sourceText: string;
@@ -65,18 +66,12 @@ export default function TranslateArea({
     try {
       // Determine the language for improvement
       const nativeLang = localStorage.getItem('nativeLang') || 'Japanese';
-      const improveLanguage = isSource ? 'Auto' : nativeLang;
-      
-      // Use appropriate language code for improvement
-      let languageCode = improveLanguage;
-      if (improveLanguage === 'Auto') {
-        // For source text, try to detect language or use a default
-        languageCode = 'English'; // Default fallback
-      }
+      // Source text is improved in whatever language the backend detects
+      const improveLanguage = isSource ? 'auto' : nativeLang;
       
       const response = await invoke<ImproveResponse>('improve_text', {
         text,
-        language: languageCode,
+        language: improveLanguage,
       });
       
       const improved = response.translated_text;
@@ -169,7 +164,7 @@ export default function TranslateArea({
             fileName: file.name
           });
         } catch (backendError) {
-          throw new Error(`${fileExtension?.toUpperCase()} ファイルの処理に失敗しました: ${backendError}`);
+          throw new Error(`${fileExtension?.toUpperCase()} ファイルの処理に失敗しました: ${describeError(backendError)}`);
         }
       }
 
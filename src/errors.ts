@@ -0,0 +1,17 @@
+// Errors returned by Tauri commands: `{ code, message }`, see src-tauri/src/error.rs
+export interface NeuralError {
+  code: string;
+  message: string;
+}
+
+export function isNeuralError(error: unknown): error is NeuralError {
+  return typeof error === 'object' && error !== null && 'code' in error && 'message' in error;
+}
+
+// Human-readable text for anything a command or the browser might throw
+export function describeError(error: unknown): string {
+  if (isNeuralError(error) || error instanceof Error) {
+    return error.message;
+  }
+  return String(error);
+}
@@ -0,0 +1,18 @@
+// Languages travel between the app and the backend as BCP 47 tags, see src-tauri/src/language.rs.
+// The backend also accepts English names, which is what the language selector uses as codes;
+// plain "Chinese" is read as Simplified Chinese, so Traditional Chinese keeps its full name.
+const SELECTOR_NAMES: Record<string, string> = {
+  'ja': 'Japanese',
+  'en': 'English',
+  'zh-CN': 'Chinese',
+  'zh-TW': 'Traditional Chinese',
+  'ko': 'Korean',
+  'es': 'Spanish',
+  'fr': 'French',
+  'de': 'German',
+};
+
+// Selector name for a backend tag; names (and tags the selector doesn't list) pass through unchanged
+export function languageName(language: string): string {
+  return SELECTOR_NAMES[language] ?? language;
+}